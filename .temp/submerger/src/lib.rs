@@ -0,0 +1,717 @@
+//! Parsing of SRT, WebVTT and ASS subtitles into [`Subtitle`]s, and merging
+//! them with [`SubtitleMerger`], which the `submerger` binary drives from its
+//! command line.
+
+use encoding_rs::Encoding;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::sync::LazyLock;
+
+mod merge;
+
+pub use merge::{
+    AssRounding, InputOptions, KeepVariant, MergeStrategy, MergeSummary, SourceInfo, SpeakerCase,
+    SpeakerStyle, SubtitleMerger, SubtitleMergerBuilder, Vocab,
+};
+
+/// One cue of an input, keyed by its start time in [`Subtitle`].
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    /// End time in ms.
+    pub end: i64,
+    pub text: String,
+    /// Name of the ASS style the event uses.
+    pub style: Option<String>,
+    /// The cue number written in the input, for SRT.
+    pub index: Option<u64>,
+    /// Line of the input holding the cue's timing, counting from 1; `0` for
+    /// cues that did not come from a parsed input.
+    pub line: usize,
+}
+
+/// A cue with its start, as [`Subtitle::cues`] lists them. Cues compare
+/// field by field: by start, then end, then text, so sorting a list puts
+/// it in playback order and equal cues are exactly the same cue.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cue {
+    /// Start time in ms.
+    pub start: i64,
+    /// End time in ms.
+    pub end: i64,
+    pub text: String,
+}
+
+impl Cue {
+    pub fn new(start: i64, end: i64, text: impl Into<String>) -> Cue {
+        Cue {
+            start,
+            end,
+            text: text.into(),
+        }
+    }
+}
+
+/// The parts of an ASS style that survive conversion to SRT.
+#[derive(Debug, Clone)]
+pub struct AssStyle {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// Style columns written to ASS output, with the values of the `Default`
+/// style used when no input defines one.
+pub const ASS_STYLE_COLUMNS: [&str; 23] = [
+    "Name",
+    "Fontname",
+    "Fontsize",
+    "PrimaryColour",
+    "SecondaryColour",
+    "OutlineColour",
+    "BackColour",
+    "Bold",
+    "Italic",
+    "Underline",
+    "StrikeOut",
+    "ScaleX",
+    "ScaleY",
+    "Spacing",
+    "Angle",
+    "BorderStyle",
+    "Outline",
+    "Shadow",
+    "Alignment",
+    "MarginL",
+    "MarginR",
+    "MarginV",
+    "Encoding",
+];
+pub const ASS_DEFAULT_STYLE: [&str; 23] = [
+    "Default",
+    "Arial",
+    "20",
+    "&H00FFFFFF",
+    "&H000000FF",
+    "&H00000000",
+    "&H00000000",
+    "0",
+    "0",
+    "0",
+    "0",
+    "100",
+    "100",
+    "0",
+    "0",
+    "1",
+    "2",
+    "2",
+    "2",
+    "10",
+    "10",
+    "10",
+    "1",
+];
+
+/// A style's name and its values in `ASS_STYLE_COLUMNS` order.
+pub type StyleLine = (String, Vec<String>);
+
+/// A parsed input.
+#[derive(Debug, Clone)]
+pub struct Subtitle {
+    /// Where the input was read from; empty for [`parse_str`].
+    pub path: String,
+    /// `srt`, `vtt` or `ass`.
+    pub format: String,
+    /// Name of the encoding the input was decoded from.
+    pub encoding: &'static str,
+    /// The ASS `PlayResX`/`PlayResY`, if declared.
+    pub play_res: Option<(u32, u32)>,
+    /// Cues by start time in ms; cues sharing a start are joined.
+    pub dialogs: HashMap<i64, Dialog>,
+    /// ASS `Comment:` events by start time in ms.
+    pub comments: HashMap<i64, Dialog>,
+    /// The bold and italic flags of each ASS style by name.
+    pub styles: HashMap<String, AssStyle>,
+    /// The styles in the order the file lists them.
+    pub style_lines: Vec<StyleLine>,
+    /// ISO 639-3 code detected with `--lang-detect`.
+    pub language: Option<String>,
+    /// ASS sections other than `[Script Info]`, the styles and `[Events]`,
+    /// such as `[Aegisub Project Garbage]`: each name with its lines as
+    /// written, in file order.
+    pub extra_sections: Vec<(String, Vec<String>)>,
+    /// Whatever an SRT file had before its first cue, such as editor
+    /// metadata, trimmed; `None` when the file starts with a cue.
+    pub preamble: Option<String>,
+}
+
+impl Subtitle {
+    /// The cues, without ASS comments, in playback order.
+    pub fn cues(&self) -> Vec<Cue> {
+        let mut cues: Vec<Cue> = self
+            .dialogs
+            .iter()
+            .map(|(&start, dialog)| Cue::new(start, dialog.end, dialog.text.as_str()))
+            .collect();
+        cues.sort();
+        cues
+    }
+}
+
+/// The failure classes a run can end with; each maps to its own exit code
+/// so scripts can tell them apart.
+#[derive(Debug)]
+pub enum MergeError {
+    /// An input is not a recognized format or does not parse.
+    Parse(String),
+    /// An input could not be read or an output could not be written.
+    Io(String),
+    /// The merge produced no cues.
+    Empty,
+    /// The arguments or the inputs failed a check.
+    Validation(String),
+}
+
+impl MergeError {
+    /// The code the binary exits with for this error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            MergeError::Parse(_) => 2,
+            MergeError::Io(_) => 3,
+            MergeError::Empty => 4,
+            MergeError::Validation(_) => 5,
+        }
+    }
+
+    /// The same kind of error with a different message.
+    pub fn with_message(&self, message: String) -> MergeError {
+        match self {
+            MergeError::Parse(_) => MergeError::Parse(message),
+            MergeError::Io(_) => MergeError::Io(message),
+            MergeError::Empty => MergeError::Empty,
+            MergeError::Validation(_) => MergeError::Validation(message),
+        }
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeError::Parse(message)
+            | MergeError::Io(message)
+            | MergeError::Validation(message) => f.write_str(message),
+            MergeError::Empty => f.write_str("The merge produced no cues"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Why a timestamp was rejected, naming the component that was wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeParseError {
+    input: String,
+    component: &'static str,
+    problem: String,
+}
+
+impl fmt::Display for TimeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid timestamp '{}': {} {}",
+            self.input, self.component, self.problem
+        )
+    }
+}
+
+impl std::error::Error for TimeParseError {}
+
+/// Parses an SRT (`00:00:01,500`), VTT (`00:00:01.500` or `00:01.500`) or
+/// ASS (`0:00:01.50`) timestamp into milliseconds. Only ASCII digits are
+/// accepted, the fraction may have any number of digits and hours may be
+/// omitted; minutes and seconds must be below 60.
+pub fn parse_timestamp(value: &str) -> Result<i64, TimeParseError> {
+    let error = |component, problem: String| TimeParseError {
+        input: value.to_string(),
+        component,
+        problem,
+    };
+    let number = |component, digits: &str| {
+        if digits.is_empty() {
+            return Err(error(component, "is missing".to_string()));
+        }
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(error(component, format!("'{}' is not a number", digits)));
+        }
+        digits
+            .parse::<i64>()
+            .map_err(|_| error(component, format!("'{}' is too large", digits)))
+    };
+
+    let (clock, fraction) = match value.find([',', '.']) {
+        Some(i) => (&value[..i], Some(&value[i + 1..])),
+        None => (value, None),
+    };
+    let fields: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match fields[..] {
+        [hours, minutes, seconds] => (number("hours", hours)?, minutes, seconds),
+        [minutes, seconds] => (0, minutes, seconds),
+        _ => return Err(error("clock", format!("'{}' is not [HH:]MM:SS", clock))),
+    };
+    let minutes = number("minutes", minutes)?;
+    if minutes >= 60 {
+        return Err(error("minutes", format!("'{}' is not below 60", minutes)));
+    }
+    let seconds = number("seconds", seconds)?;
+    if seconds >= 60 {
+        return Err(error("seconds", format!("'{}' is not below 60", seconds)));
+    }
+    let millis = match fraction {
+        Some(digits) => {
+            number("fraction", digits)?;
+            // Pad or cut to three digits so `.5`, `.50` and `.500` agree.
+            format!("{:0<3}", &digits[..digits.len().min(3)])
+                .parse::<i64>()
+                .unwrap()
+        }
+        None => 0,
+    };
+    hours
+        .checked_mul(3_600_000)
+        .and_then(|ms| ms.checked_add((minutes * 60 + seconds) * 1000 + millis))
+        .ok_or_else(|| error("hours", format!("'{}' is too large", hours)))
+}
+
+/// Formats milliseconds as an SRT timestamp (`00:00:01,500`).
+pub fn format_timestamp(ms: i64) -> String {
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Quotes `value` as a JSON string, escaping quotes, backslashes and
+/// control characters.
+pub fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Guesses the format from the content: `srt`, `vtt`, `ass` or `unknown`.
+pub fn detect_format(content: &str) -> &'static str {
+    if content.trim_start().starts_with("WEBVTT") {
+        "vtt"
+    } else if content.contains("-->") {
+        "srt"
+    } else if content.contains("Dialogue:") {
+        "ass"
+    } else {
+        "unknown"
+    }
+}
+
+/// Decodes raw input bytes, returning the text and the name of the encoding
+/// used: the BOM's if present, else UTF-8 if the bytes are valid UTF-8, else
+/// Windows-1252.
+pub fn decode(bytes: &[u8]) -> (String, &'static str) {
+    let encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => encoding,
+        None if std::str::from_utf8(bytes).is_ok() => encoding_rs::UTF_8,
+        None => encoding_rs::WINDOWS_1252,
+    };
+    let (content, encoding, _) = encoding.decode(bytes);
+    (content.into_owned(), encoding.name())
+}
+
+/// Decodes each blank-line separated block of `bytes` on its own with
+/// [`decode`], to salvage files joined from parts in different encodings.
+/// Returns the text and each encoding used with its number of blocks, in
+/// order of first use; ASCII blocks read the same in all and count for
+/// none. Input with a byte order mark is decoded whole, as
+/// its line breaks may not be single bytes.
+pub fn decode_blocks(bytes: &[u8]) -> (String, Vec<(&'static str, usize)>) {
+    if Encoding::for_bom(bytes).is_some() {
+        let (content, encoding) = decode(bytes);
+        return (content, vec![(encoding, 1)]);
+    }
+    let mut content = String::new();
+    let mut encodings: Vec<(&'static str, usize)> = Vec::new();
+    let mut block_start = 0;
+    let mut pos = 0;
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        pos += line.len();
+        let blank = line.iter().all(|b| b.is_ascii_whitespace());
+        if !blank && pos < bytes.len() {
+            continue;
+        }
+        let block = &bytes[block_start..pos];
+        block_start = pos;
+        let (text, encoding) = decode(block);
+        content.push_str(&text);
+        if block.is_ascii() {
+            continue;
+        }
+        match encodings.iter_mut().find(|(name, _)| *name == encoding) {
+            Some((_, count)) => *count += 1,
+            None => encodings.push((encoding, 1)),
+        }
+    }
+    if encodings.is_empty() {
+        encodings.push((encoding_rs::UTF_8.name(), 1));
+    }
+    (content, encodings)
+}
+
+/// Reads, decodes and parses a subtitle file, detecting its format from the
+/// content.
+pub fn parse_file(path: impl AsRef<Path>) -> Result<Subtitle, MergeError> {
+    let path = path.as_ref();
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|e| MergeError::Io(format!("Cannot read '{}': {}", path.display(), e)))?;
+    let (content, encoding) = decode(&bytes);
+    let mut subtitle = parse_str(&content, detect_format(&content))
+        .map_err(|e| e.with_message(format!("Cannot parse '{}': {}", path.display(), e)))?;
+    subtitle.path = path.to_string_lossy().into_owned();
+    subtitle.encoding = encoding;
+    Ok(subtitle)
+}
+
+/// Parses subtitle text in `format`, `srt`, `vtt` or `ass`. ASS comments are kept
+/// in [`Subtitle::comments`].
+pub fn parse_str(content: &str, format: &str) -> Result<Subtitle, MergeError> {
+    let mut subtitle = Subtitle {
+        path: String::new(),
+        format: format.to_string(),
+        encoding: "UTF-8",
+        play_res: None,
+        dialogs: HashMap::new(),
+        comments: HashMap::new(),
+        styles: HashMap::new(),
+        style_lines: Vec::new(),
+        language: None,
+        extra_sections: Vec::new(),
+        preamble: None,
+    };
+
+    let parsed = match format {
+        "srt" => parse_srt(content, &mut subtitle),
+        "vtt" => parse_vtt(content, &mut subtitle),
+        "ass" => parse_ass(content, &mut subtitle).map(|()| {
+            subtitle.play_res = parse_play_res(content);
+            (subtitle.styles, subtitle.style_lines) = parse_styles(content);
+            subtitle.extra_sections = parse_extra_sections(content);
+        }),
+        _ => Err("Unsupported subtitle format".into()),
+    };
+    parsed.map_err(|e| MergeError::Parse(e.to_string()))?;
+    Ok(subtitle)
+}
+
+/// Adds `dialog` at `start`, joining it onto a cue that already starts
+/// there.
+pub fn insert_dialog(dialogs: &mut HashMap<i64, Dialog>, start: i64, dialog: Dialog) {
+    match dialogs.entry(start) {
+        std::collections::hash_map::Entry::Occupied(mut entry) => {
+            let existing = entry.get_mut();
+            existing.end = existing.end.max(dialog.end);
+            existing.text = format!("{}\n{}", existing.text, dialog.text);
+        }
+        std::collections::hash_map::Entry::Vacant(entry) => {
+            entry.insert(dialog);
+        }
+    }
+}
+
+// Some tools write the milliseconds after a dot, VTT-style; both are
+// accepted and the output always uses the canonical comma.
+static SRT_TIMING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{1,2}:\d{1,2}:\d{1,2}[,.]\d{1,5}) --> (\d{1,2}:\d{1,2}:\d{1,2}[,.]\d{1,5})")
+        .unwrap()
+});
+
+fn parse_srt(content: &str, subtitle: &mut Subtitle) -> Result<(), Box<dyn std::error::Error>> {
+    // Anything before the first cue's timing line and its index is skipped
+    // as a whole, so junk that runs straight into the first cue without a
+    // blank line is neither taken as its index nor as a cue of its own.
+    let first_cue = SRT_TIMING.find(content).map_or(content.len(), |timing| {
+        let before = content[..timing.start()].trim_end();
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        if before[line_start..].trim().parse::<u64>().is_ok() {
+            line_start
+        } else {
+            timing.start()
+        }
+    });
+    let (preamble, content) = content.split_at(first_cue);
+    if !preamble.trim().is_empty() {
+        subtitle.preamble = Some(preamble.trim().to_string());
+    }
+
+    let mut line = preamble.matches('\n').count() + 1;
+    for dialog_block in content.split("\n\n") {
+        let block_line = line;
+        line += dialog_block.matches('\n').count() + 2;
+        if let Some(caps) = SRT_TIMING.captures(dialog_block) {
+            let start = parse_timestamp(&caps[1])?;
+            let end = parse_timestamp(&caps[2])?;
+
+            let timing = caps.get(0).unwrap();
+            let text = dialog_block[timing.end()..].trim().to_string();
+            let index = dialog_block[..timing.start()].trim().parse().ok();
+
+            let dialog = Dialog {
+                end,
+                text,
+                style: None,
+                index,
+                line: block_line + dialog_block[..timing.start()].matches('\n').count(),
+            };
+            insert_dialog(&mut subtitle.dialogs, start, dialog);
+        }
+    }
+
+    Ok(())
+}
+
+static VTT_TIMING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:\d+:)?\d{1,2}:\d{1,2}\.\d+)[ \t]+-->[ \t]+((?:\d+:)?\d{1,2}:\d{1,2}\.\d+)")
+        .unwrap()
+});
+
+/// Reads WebVTT cues, whose timestamps may leave out the hour. Settings
+/// after the timing are dropped; the header and `NOTE`, `STYLE` and
+/// `REGION` blocks have no timing line and are skipped.
+fn parse_vtt(content: &str, subtitle: &mut Subtitle) -> Result<(), Box<dyn std::error::Error>> {
+    let mut blocks = vec![Vec::new()];
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            blocks.push(Vec::new());
+        } else {
+            blocks.last_mut().unwrap().push((number + 1, line));
+        }
+    }
+
+    for block in blocks {
+        // The timing line comes first, or after the cue's identifier.
+        let Some((position, caps)) = block
+            .iter()
+            .take(2)
+            .enumerate()
+            .find_map(|(i, (_, line))| VTT_TIMING.captures(line).map(|caps| (i, caps)))
+        else {
+            continue;
+        };
+        let start = parse_timestamp(&caps[1])?;
+        let end = parse_timestamp(&caps[2])?;
+        let dialog = Dialog {
+            end,
+            text: block[position + 1..]
+                .iter()
+                .map(|(_, line)| *line)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            style: None,
+            index: (position == 1)
+                .then(|| block[0].1.trim().parse().ok())
+                .flatten(),
+            line: block[position].0,
+        };
+        insert_dialog(&mut subtitle.dialogs, start, dialog);
+    }
+
+    Ok(())
+}
+
+/// Reads the `Dialogue:` and `Comment:` events. A `Timer:` other than
+/// 100 in `[Script Info]` is applied here, so later stages only ever see
+/// real times.
+fn parse_ass(content: &str, subtitle: &mut Subtitle) -> Result<(), Box<dyn std::error::Error>> {
+    // Until the [Events] Format: line says otherwise, assume the
+    // standard v4+ column order.
+    let mut columns: Vec<String> = [
+        "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+    ]
+    .map(String::from)
+    .to_vec();
+    let mut in_events = false;
+    let mut in_script_info = false;
+    // `Timer:` is the playback speed in percent; above 100 the script
+    // runs faster, so real times are the written ones divided by it.
+    let mut speed = 1.0;
+
+    for (number, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('[') {
+            let section = trimmed.trim_end();
+            in_events = section.eq_ignore_ascii_case("[Events]");
+            in_script_info = section.eq_ignore_ascii_case("[Script Info]");
+            continue;
+        }
+        if in_script_info {
+            if let Some(timer) = trimmed.strip_prefix("Timer:") {
+                speed = match timer.trim().parse::<f64>() {
+                    Ok(percent) if percent > 0.0 => percent / 100.0,
+                    _ => return Err(format!("invalid ASS Timer: '{}'", timer.trim()).into()),
+                };
+            }
+            continue;
+        }
+        if let Some(format) = trimmed.strip_prefix("Format:") {
+            if in_events {
+                columns = format.split(',').map(|c| c.trim().to_lowercase()).collect();
+            }
+            continue;
+        }
+        let (is_comment, event) = if let Some(event) = trimmed.strip_prefix("Dialogue:") {
+            (false, event)
+        } else if let Some(event) = trimmed.strip_prefix("Comment:") {
+            (true, event)
+        } else {
+            continue;
+        };
+        // Only the last column may contain commas, so it takes the rest.
+        // Splitting instead of matching a pattern keeps this linear even on
+        // huge lines such as long `\p` drawings.
+        let values: Vec<&str> = event.trim_start().splitn(columns.len(), ',').collect();
+        let field = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .and_then(|i| values.get(i).copied())
+        };
+        let (Some(start), Some(end)) = (field("start"), field("end")) else {
+            return Err(format!("ASS event without Start/End fields: {}", line).into());
+        };
+        let real_time = |ms: i64| (ms as f64 / speed).round() as i64;
+        let start = real_time(parse_timestamp(start.trim())?);
+        let end = real_time(parse_timestamp(end.trim())?);
+
+        let dialog = Dialog {
+            end,
+            text: field("text").unwrap_or("").to_string(),
+            style: field("style").map(|style| style.trim().to_string()),
+            index: None,
+            line: number + 1,
+        };
+
+        let dialogs = if is_comment {
+            &mut subtitle.comments
+        } else {
+            &mut subtitle.dialogs
+        };
+        insert_dialog(dialogs, start, dialog);
+    }
+
+    Ok(())
+}
+
+/// Reads the styles by their `Format:` columns, also keeping every
+/// style's values rearranged into `ASS_STYLE_COLUMNS` so ASS output can
+/// write them back; missing columns take the `Default` style's value.
+fn parse_styles(content: &str) -> (HashMap<String, AssStyle>, Vec<StyleLine>) {
+    let mut styles = HashMap::new();
+    let mut style_lines = Vec::new();
+    let mut columns: Vec<String> = Vec::new();
+    let mut in_styles = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_styles = line.eq_ignore_ascii_case("[V4+ Styles]")
+                || line.eq_ignore_ascii_case("[V4 Styles]");
+        } else if !in_styles {
+            continue;
+        } else if let Some(format) = line.strip_prefix("Format:") {
+            columns = format.split(',').map(|c| c.trim().to_lowercase()).collect();
+        } else if let Some(style) = line.strip_prefix("Style:") {
+            let values: Vec<&str> = style.split(',').map(str::trim).collect();
+            let field = |name: &str| {
+                columns
+                    .iter()
+                    .position(|column| column == name)
+                    .and_then(|i| values.get(i).copied())
+            };
+            // ASS writes true as -1, but some tools write 1
+            let flag = |name: &str| field(name).is_some_and(|v| v != "0" && !v.is_empty());
+            if let Some(name) = field("name") {
+                let values = ASS_STYLE_COLUMNS
+                    .iter()
+                    .zip(ASS_DEFAULT_STYLE)
+                    .map(|(column, default)| {
+                        field(&column.to_lowercase()).unwrap_or(default).to_string()
+                    })
+                    .collect();
+                style_lines.push((name.to_string(), values));
+                styles.insert(
+                    name.to_string(),
+                    AssStyle {
+                        bold: flag("bold"),
+                        italic: flag("italic"),
+                    },
+                );
+            }
+        }
+    }
+    (styles, style_lines)
+}
+
+/// Collects the sections the parser has no use for, without the blank
+/// lines that end them.
+fn parse_extra_sections(content: &str) -> Vec<(String, Vec<String>)> {
+    const KNOWN: [&str; 4] = ["[Script Info]", "[V4+ Styles]", "[V4 Styles]", "[Events]"];
+    let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+    let mut in_extra = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_extra = !KNOWN
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(trimmed));
+            if in_extra {
+                let name = trimmed[1..trimmed.len() - 1].to_string();
+                sections.push((name, Vec::new()));
+            }
+        } else if in_extra {
+            sections.last_mut().unwrap().1.push(line.to_string());
+        }
+    }
+    for (_, lines) in &mut sections {
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+    }
+    sections
+}
+
+static PLAY_RES_X: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^PlayResX:\s*(\d+)").unwrap());
+static PLAY_RES_Y: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^PlayResY:\s*(\d+)").unwrap());
+
+fn parse_play_res(content: &str) -> Option<(u32, u32)> {
+    let field = |regex: &Regex| {
+        regex
+            .captures(content)
+            .and_then(|caps| caps[1].parse().ok())
+    };
+    Some((field(&PLAY_RES_X)?, field(&PLAY_RES_Y)?))
+}
@@ -1,163 +1,1829 @@
-use chrono::{NaiveTime, Timelike};
-use clap::{Arg, Command};
-use encoding_rs_io::DecodeReaderBytesBuilder;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, Command};
 use regex::Regex;
+use std::fmt;
 use std::fs::File;
-use std::io::{BufReader, Read, Write};
-use std::path::Path;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use submerger::{
+    format_timestamp, json_string, parse_file, parse_timestamp, AssRounding, InputOptions,
+    KeepVariant, MergeError, MergeStrategy, SourceInfo, SpeakerCase, SpeakerStyle, Subtitle,
+    SubtitleMerger, Vocab,
+};
 
-const WHITE: &str = "#FFFFFF";
+/// What `stats` reports about one file. Durations are in ms; a cue is
+/// identified by its start.
+struct CueStats {
+    path: String,
+    format: String,
+    encoding: &'static str,
+    cues: usize,
+    /// Time at least one cue is shown, counting overlaps once.
+    on_screen: i64,
+    average: i64,
+    median: i64,
+    /// (duration, start) of the shortest and the longest cue.
+    shortest: (i64, i64),
+    longest: (i64, i64),
+    /// Stretches with no cue shown between the first and the last cue.
+    gaps: usize,
+    total_gap: i64,
+    longest_gap: i64,
+}
+
+impl CueStats {
+    /// None if the file has no cues.
+    fn of(subtitle: &Subtitle) -> Option<CueStats> {
+        let mut cues: Vec<(i64, i64)> = subtitle
+            .dialogs
+            .iter()
+            .map(|(&start, dialog)| (start, dialog.end))
+            .collect();
+        cues.sort_unstable();
+        let mut durations: Vec<(i64, i64)> = cues
+            .iter()
+            .map(|&(start, end)| (end - start, start))
+            .collect();
+        durations.sort_unstable();
+        let (&shortest, &longest) = (durations.first()?, durations.last()?);
+        let middle = durations.len() / 2;
+        let median = if durations.len().is_multiple_of(2) {
+            (durations[middle - 1].0 + durations[middle].0) / 2
+        } else {
+            durations[middle].0
+        };
+
+        let (mut on_screen, mut gaps, mut total_gap, mut longest_gap) = (0, 0, 0, 0);
+        let mut shown_until: Option<i64> = None;
+        for &(start, end) in &cues {
+            match shown_until {
+                Some(until) if start > until => {
+                    gaps += 1;
+                    total_gap += start - until;
+                    longest_gap = longest_gap.max(start - until);
+                    on_screen += end - start;
+                }
+                Some(until) => on_screen += (end - until).max(0),
+                None => on_screen += end - start,
+            }
+            shown_until = Some(shown_until.map_or(end, |until| until.max(end)));
+        }
+
+        Some(CueStats {
+            path: subtitle.path.clone(),
+            format: subtitle.format.clone(),
+            encoding: subtitle.encoding,
+            cues: cues.len(),
+            on_screen,
+            average: durations.iter().map(|(duration, _)| duration).sum::<i64>()
+                / cues.len() as i64,
+            median,
+            shortest,
+            longest,
+            gaps,
+            total_gap,
+            longest_gap,
+        })
+    }
 
-#[derive(Debug, Clone)]
-struct Subtitle {
-    dialogs: std::collections::HashMap<i64, String>,
+    fn to_json(&self) -> String {
+        let fields = [
+            ("path", json_string(&self.path)),
+            ("format", json_string(&self.format)),
+            ("encoding", json_string(self.encoding)),
+            ("cues", self.cues.to_string()),
+            ("on_screen_ms", self.on_screen.to_string()),
+            ("average_ms", self.average.to_string()),
+            ("median_ms", self.median.to_string()),
+            ("shortest_ms", self.shortest.0.to_string()),
+            ("shortest_start_ms", self.shortest.1.to_string()),
+            ("longest_ms", self.longest.0.to_string()),
+            ("longest_start_ms", self.longest.1.to_string()),
+            ("gaps", self.gaps.to_string()),
+            ("total_gap_ms", self.total_gap.to_string()),
+            ("longest_gap_ms", self.longest_gap.to_string()),
+        ];
+        let entries: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| format!("  \"{}\": {}", name, value))
+            .collect();
+        format!("{{\n{}\n}}\n", entries.join(",\n"))
+    }
 }
 
-struct SubtitleMerger {
-    subtitles: Vec<Subtitle>,
-    output_path: String,
+impl fmt::Display for CueStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cue = |(duration, start): (i64, i64)| {
+            format!(
+                "{} (cue at {})",
+                format_timestamp(duration),
+                format_timestamp(start)
+            )
+        };
+        writeln!(f, "'{}': {}, {}", self.path, self.format, self.encoding)?;
+        writeln!(f, "  cues:         {}", self.cues)?;
+        writeln!(f, "  on screen:    {}", format_timestamp(self.on_screen))?;
+        writeln!(f, "  average:      {}", format_timestamp(self.average))?;
+        writeln!(f, "  median:       {}", format_timestamp(self.median))?;
+        writeln!(f, "  shortest:     {}", cue(self.shortest))?;
+        writeln!(f, "  longest:      {}", cue(self.longest))?;
+        writeln!(
+            f,
+            "  gaps:         {}, {} in total, longest {}",
+            self.gaps,
+            format_timestamp(self.total_gap),
+            format_timestamp(self.longest_gap)
+        )
+    }
+}
+
+/// Parses a `HH:MM:SS,mmm` (or `.mmm`) timestamp given on the command line.
+fn parse_cli_timestamp(value: &str) -> Result<i64, String> {
+    parse_timestamp(value).map_err(|e| e.to_string())
+}
+
+/// Parses `--anchor`'s `TIME1=TIME2` into both times in milliseconds.
+fn parse_anchor(value: &str) -> Result<(i64, i64), String> {
+    let (first, other) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected TIME1=TIME2, got '{}'", value))?;
+    Ok((
+        parse_cli_timestamp(first.trim())?,
+        parse_cli_timestamp(other.trim())?,
+    ))
 }
 
-impl SubtitleMerger {
-    fn new(output_path: &str) -> Self {
-        SubtitleMerger {
-            subtitles: Vec::new(),
-            output_path: output_path.to_string(),
+/// Parses `--cut-range`'s `START-END[@N]` into both times in milliseconds
+/// and the input number it is limited to, if any.
+fn parse_cut_range(value: &str) -> Result<(i64, i64, Option<usize>), String> {
+    let (range, input) = match value.rsplit_once('@') {
+        Some((range, input)) => {
+            let input = input
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&input| input > 0)
+                .ok_or_else(|| format!("expected an input number after '@', got '{}'", input))?;
+            (range, Some(input))
         }
+        None => (value, None),
+    };
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| format!("expected START-END, got '{}'", value))?;
+    let (start, end) = (
+        parse_cli_timestamp(start.trim())?,
+        parse_cli_timestamp(end.trim())?,
+    );
+    if end <= start {
+        return Err(format!("the range '{}' ends before it starts", range));
     }
+    Ok((start, end, input))
+}
 
-    fn detect_format(content: &str) -> &'static str {
-        if content.contains("-->") {
-            "srt"
-        } else if content.contains("Dialogue:") {
-            "ass"
+/// Parses a signed time delta into milliseconds: `250ms`, `2.5s`, a
+/// timestamp like `00:00:02,500`, or bare milliseconds, each optionally
+/// prefixed with `-` or `+`.
+fn parse_duration(value: &str) -> Result<i64, String> {
+    let (sign, magnitude) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let invalid = || {
+        format!(
+            "invalid duration '{}': expected e.g. '250ms', '2.5s' or '00:00:02,500'",
+            value
+        )
+    };
+    let decimal = |digits: &str| {
+        if digits.starts_with(|c: char| c.is_ascii_digit())
+            && digits.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+        {
+            digits.parse::<f64>().map_err(|_| invalid())
         } else {
-            "unknown"
+            Err(invalid())
         }
+    };
+    let ms = if magnitude.contains(':') {
+        parse_timestamp(magnitude).map_err(|e| e.to_string())?
+    } else if let Some(ms) = magnitude.strip_suffix("ms") {
+        decimal(ms)?.round() as i64
+    } else if let Some(seconds) = magnitude.strip_suffix('s') {
+        (decimal(seconds)? * 1000.0).round() as i64
+    } else {
+        magnitude.parse::<u64>().map_err(|_| invalid())? as i64
+    };
+    Ok(sign * ms)
+}
+
+/// Parses an ASS numpad alignment given as `an8` or `8`.
+fn parse_alignment(value: &str) -> Result<u8, String> {
+    match value.strip_prefix("an").unwrap_or(value).parse::<u8>() {
+        Ok(alignment @ 1..=9) => Ok(alignment),
+        _ => Err(format!(
+            "invalid alignment '{}': expected an1 to an9",
+            value
+        )),
     }
+}
 
-    fn add(&mut self, subtitle_address: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let mut file = File::open(subtitle_address)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
+fn parse_positive_duration(value: &str) -> Result<i64, String> {
+    match parse_duration(value)? {
+        ms if ms > 0 => Ok(ms),
+        _ => Err(format!("duration '{}' must be positive", value)),
+    }
+}
 
-        let mut subtitle = Subtitle {
-            dialogs: std::collections::HashMap::new(),
-        };
+fn parse_non_negative_duration(value: &str) -> Result<i64, String> {
+    match parse_duration(value)? {
+        ms if ms >= 0 => Ok(ms),
+        _ => Err(format!("duration '{}' must not be negative", value)),
+    }
+}
+
+/// A factor or rate: finite and above zero, so `0`, `-1`, `NaN` and `inf`
+/// are refused rather than collapsing or overflowing every timestamp.
+fn parse_positive_number(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() && number > 0.0 => Ok(number),
+        _ => Err(format!(
+            "'{}' must be a finite number greater than 0",
+            value
+        )),
+    }
+}
 
-        match Self::detect_format(&content) {
-            "srt" => self.parse_srt(&content, &mut subtitle)?,
-            "ass" => self.parse_ass(&content, &mut subtitle)?,
-            _ => return Err("Unsupported subtitle format".into()),
+/// A window given in milliseconds, or in frames with an `f` suffix.
+#[derive(Debug, Clone, Copy)]
+enum Tolerance {
+    Millis(i64),
+    Frames(f64),
+}
+
+impl Tolerance {
+    fn to_ms(self, fps: Option<f64>) -> Result<i64, String> {
+        match (self, fps) {
+            (Tolerance::Millis(ms), _) => Ok(ms),
+            (Tolerance::Frames(frames), Some(fps)) => Ok((frames * 1000.0 / fps).round() as i64),
+            (Tolerance::Frames(_), None) => {
+                Err("frame-based values like '2f' require --fps".to_string())
+            }
         }
+    }
+}
 
-        self.subtitles.push(subtitle);
-        Ok(())
+/// The window of option `id` in ms. Precedence, highest first: the
+/// option itself, the global `--tolerance`, then the option's own default;
+/// `None` if none of them is set.
+fn window_ms(
+    matches: &clap::ArgMatches,
+    id: &str,
+    fps: Option<f64>,
+) -> Result<Option<i64>, MergeError> {
+    let given = matches
+        .value_source(id)
+        .is_some_and(|source| source != ValueSource::DefaultValue);
+    let window = if given {
+        matches.get_one::<Tolerance>(id)
+    } else {
+        matches
+            .get_one::<Tolerance>("tolerance")
+            .or_else(|| matches.get_one::<Tolerance>(id))
+    };
+    window
+        .map(|window| window.to_ms(fps).map_err(MergeError::Validation))
+        .transpose()
+}
+
+fn parse_tolerance(value: &str) -> Result<Tolerance, String> {
+    let invalid = || {
+        format!(
+            "invalid window '{}': expected a duration or frames like '2f'",
+            value
+        )
+    };
+    match value.strip_suffix('f') {
+        Some(frames) => match frames.parse::<f64>() {
+            Ok(frames) if frames >= 0.0 => Ok(Tolerance::Frames(frames)),
+            _ => Err(invalid()),
+        },
+        None => match parse_duration(value)? {
+            ms if ms >= 0 => Ok(Tolerance::Millis(ms)),
+            _ => Err(invalid()),
+        },
     }
+}
 
-    fn parse_srt(
-        &self,
-        content: &str,
-        subtitle: &mut Subtitle,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let time_regex =
-            Regex::new(r"\d{1,2}:\d{1,2}:\d{1,2},\d{1,5} --> \d{1,2}:\d{1,2}:\d{1,2},\d{1,5}")?;
+/// Expands `{base}`, `{langs}` and `{date}` in an output name. `{base}` is
+/// the stem the inputs share (`movie` for `movie.en.srt` and `movie.de.srt`)
+/// and `{langs}` joins each input's detected language, or else what follows
+/// the shared stem, with `-`.
+fn expand_output_template(template: &str, sources: &[SourceInfo]) -> Result<String, String> {
+    let stems: Vec<String> = sources
+        .iter()
+        .map(|source| {
+            let input = source.path.as_str();
+            let name = input.rsplit(['/', '\\']).next().unwrap_or(input);
+            Path::new(name)
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+        })
+        .collect();
+    let mut common = stems[0].len();
+    for stem in &stems[1..] {
+        common = stems[0]
+            .char_indices()
+            .zip(stem.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(common);
+    }
+    let is_separator = |c: char| matches!(c, '.' | '-' | '_' | ' ');
+    let base = stems[0][..common].trim_end_matches(is_separator);
+    let langs: Vec<&str> = stems
+        .iter()
+        .zip(sources)
+        .map(|(stem, source)| match &source.language {
+            Some(language) => language.as_str(),
+            None => stem[common..].trim_start_matches(is_separator),
+        })
+        .filter(|lang| !lang.is_empty())
+        .collect();
 
-        for dialog_block in content.split("\n\n") {
-            if let Some(time_match) = time_regex.find(dialog_block) {
-                let time_str = time_match.as_str().split(" --> ").next().unwrap();
-                let time = NaiveTime::parse_from_str(time_str, "%H:%M:%S")?;
-                let timestamp =
-                    time.hour() as i64 * 3600 + time.minute() as i64 * 60 + time.second() as i64;
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}') else {
+            return Err(format!("unclosed '{{' in output template '{}'", template));
+        };
+        match &rest[open + 1..open + close] {
+            "base" => output.push_str(base),
+            "langs" => output.push_str(&langs.join("-")),
+            "date" => output.push_str(&chrono::Local::now().format("%Y-%m-%d").to_string()),
+            other => {
+                return Err(format!(
+                    "unknown placeholder '{{{}}}' in output template '{}'; expected {{base}}, {{langs}} or {{date}}",
+                    other, template
+                ))
+            }
+        }
+        rest = &rest[open + close + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
 
-                let text = dialog_block.replace(time_str, "").trim().to_string();
+/// Keeps the inputs that a selector names, either by their 1-based number
+/// or by a substring of their file name, in input order.
+fn keep_only_sources<'a>(
+    inputs: Vec<&'a String>,
+    selectors: &[&String],
+) -> Result<Vec<&'a String>, MergeError> {
+    let total = inputs.len();
+    let kept: Vec<&String> = inputs
+        .into_iter()
+        .enumerate()
+        .filter(|(i, input)| {
+            let name = Path::new(input.as_str()).file_name().map_or_else(
+                || input.to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            selectors
+                .iter()
+                .any(|selector| match selector.parse::<usize>() {
+                    Ok(number) => number == i + 1,
+                    Err(_) => name.contains(selector.as_str()),
+                })
+        })
+        .map(|(_, input)| input)
+        .collect();
+    if kept.is_empty() {
+        return Err(MergeError::Validation(format!(
+            "--keep-only-sources matched none of the {} inputs",
+            total
+        )));
+    }
+    Ok(kept)
+}
 
-                subtitle
-                    .dialogs
-                    .entry(timestamp)
-                    .and_modify(|existing| *existing = format!("{}\n{}", existing, text))
-                    .or_insert(text);
+/// Reads a per-input flag: a single value applies to every input, otherwise
+/// one value is expected per input.
+fn per_input<T: Clone + Send + Sync + 'static>(
+    matches: &clap::ArgMatches,
+    name: &str,
+    inputs: usize,
+) -> Result<Option<Vec<T>>, MergeError> {
+    let Some(values) = matches.get_many::<T>(name) else {
+        return Ok(None);
+    };
+    let values: Vec<T> = values.cloned().collect();
+    match values.len() {
+        1 => Ok(Some(vec![values[0].clone(); inputs])),
+        n if n == inputs => Ok(Some(values)),
+        n => Err(MergeError::Validation(format!(
+            "--{} expects 1 or {} values, got {}",
+            name, inputs, n
+        ))),
+    }
+}
+
+/// The lines of `--header-comment`: the version and time of the run, the
+/// inputs and the options given on the command line, in the order given
+/// and by their long names, whatever their spelling: `-q` and
+/// `--merge-strategy=vertical` are listed as `--quiet` and
+/// `--merge-strategy vertical`.
+fn header_comment(matches: &clap::ArgMatches, sources: &[SourceInfo]) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "Merged by submerger {} on {}",
+            env!("CARGO_PKG_VERSION"),
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        ),
+        format!(
+            "Inputs: {}",
+            sources
+                .iter()
+                .map(|source| source.path.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    ];
+    let command = cli();
+    let mut given: Vec<(usize, &Arg)> = command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional())
+        .filter(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .filter_map(|arg| Some((matches.index_of(arg.get_id().as_str())?, arg)))
+        .collect();
+    given.sort_unstable_by_key(|&(index, _)| index);
+    let mut options: Vec<String> = Vec::new();
+    for (_, arg) in given {
+        let id = arg.get_id().as_str();
+        let name = match (arg.get_long(), arg.get_short()) {
+            (Some(long), _) => format!("--{}", long),
+            (None, Some(short)) => format!("-{}", short),
+            (None, None) => continue,
+        };
+        if matches!(arg.get_action(), ArgAction::SetTrue | ArgAction::SetFalse) {
+            options.push(name);
+            continue;
+        }
+        let delimiter = arg
+            .get_value_delimiter()
+            .map_or(" ".to_string(), String::from);
+        for values in matches.get_raw_occurrences(id).into_iter().flatten() {
+            let values: Vec<String> = values
+                .map(|value| value.to_string_lossy().into_owned())
+                .collect();
+            options.push(name.clone());
+            if !values.is_empty() {
+                options.push(values.join(&delimiter));
             }
         }
+    }
+    if !options.is_empty() {
+        lines.push(format!("Options: {}", options.join(" ")));
+    }
+    lines
+}
 
-        Ok(())
+/// Reads `--vocab`'s list, one word or phrase per line, and applies the
+/// `FORMAT=TEMPLATE` overrides of `--vocab-wrap`.
+fn read_vocab(path: &Path, wraps: &[&String]) -> Result<Vocab, MergeError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| MergeError::Io(format!("Cannot read '{}': {}", path.display(), e)))?;
+    let words: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    if words.is_empty() {
+        return Err(MergeError::Validation(format!(
+            "'{}' lists no words",
+            path.display()
+        )));
+    }
+    let mut vocab = Vocab::new(&words);
+    for wrap in wraps {
+        let (format, template) = wrap
+            .split_once('=')
+            .filter(|(_, template)| template.contains("{}"))
+            .ok_or_else(|| {
+                MergeError::Validation(format!(
+                    "--vocab-wrap expects FORMAT=TEMPLATE with {{}} for the word, got '{}'",
+                    wrap
+                ))
+            })?;
+        let target = match format {
+            "srt" => &mut vocab.srt,
+            "vtt" => &mut vocab.vtt,
+            "ass" => &mut vocab.ass,
+            _ => {
+                return Err(MergeError::Validation(format!(
+                    "--vocab-wrap expects srt, vtt or ass before '=', got '{}'",
+                    format
+                )))
+            }
+        };
+        *target = template.to_string();
     }
+    Ok(vocab)
+}
+
+/// Reads `--style-from`'s script, which must be ASS to have styles.
+fn read_style_source(path: &Path) -> Result<Subtitle, MergeError> {
+    let script = parse_file(path)?;
+    if script.format != "ass" {
+        return Err(MergeError::Validation(format!(
+            "--style-from expects an ASS script, but '{}' is {}",
+            path.display(),
+            script.format
+        )));
+    }
+    Ok(script)
+}
+
+/// Reads a shot-change list: one timecode per line, blank lines ignored.
+fn read_shot_changes(path: &Path) -> Result<Vec<i64>, MergeError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| MergeError::Io(format!("Cannot read '{}': {}", path.display(), e)))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_timestamp(line.trim()).map_err(|e| {
+                MergeError::Parse(format!("'{}' line {}: {}", path.display(), i + 1, e))
+            })
+        })
+        .collect()
+}
+
+fn cli() -> Command {
+    Command::new("subtitle-merger")
+        .about("Merge subtitle files")
+        .after_help(
+            "Exit codes:\n  0  success\n  2  an input is not a known format or does not parse\n  \
+             3  an input cannot be read or an output cannot be written\n  \
+             4  the merge produced no cues\n  5  invalid arguments or a failed check",
+        )
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("stats")
+                .about("Print cue statistics of one file")
+                .arg(Arg::new("file").required(true))
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the statistics as JSON"),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Preview the merge of inputs on a local web page (needs the 'serve' feature)")
+                .arg(Arg::new("inputs").required(true).num_args(1..))
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .value_parser(clap::value_parser!(u16))
+                        .default_value("8000")
+                        .help("Port on 127.0.0.1 to listen on; 0 picks a free one"),
+                ),
+        )
+        .arg(Arg::new("input1").index(1).required(true))
+        .arg(Arg::new("input2").index(2).required(true))
+        .arg(
+            Arg::new("add-input")
+                .long("add-input")
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .help("Merge a further input after the two positional ones; repeat for more"),
+        )
+        .arg(
+            Arg::new("keep-only-sources")
+                .long("keep-only-sources")
+                .value_name("SOURCE,...")
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("Merge only the inputs matching one of these, each an input number or a substring of the file name; per-input options then count only those inputs"),
+        )
+        .arg(
+            Arg::new("output")
+                .index(3)
+                .required_unless_present_any([
+                    "preview",
+                    "output-template",
+                    "check",
+                    "offset-preview",
+                    "overlap-report",
+                ])
+                .conflicts_with("output-template")
+                .help("File to write the merge to, or - for stdout"),
+        )
+        .arg(
+            Arg::new("output-template")
+                .long("output-template")
+                .value_name("TEMPLATE")
+                .help("Name the output from the inputs instead, e.g. '{base}.{langs}.merged.srt'; {date} adds today's date"),
+        )
+        .arg(
+            Arg::new("keep-positions")
+                .long("keep-positions")
+                .action(ArgAction::SetTrue)
+                .help("Warn about differing ASS PlayRes instead of rescaling positional tags"),
+        )
+        .arg(
+            Arg::new("include-comments")
+                .long("include-comments")
+                .action(ArgAction::SetTrue)
+                .help("Also read ASS Comment: events; they are kept in ASS output and skipped in SRT"),
+        )
+        .arg(
+            Arg::new("merge-strategy")
+                .long("merge-strategy")
+                .value_parser(["vertical", "sequential", "overwrite"])
+                .default_value("sequential")
+                .help("vertical: stack cues into one; sequential: separate numbered entries; overwrite: later inputs replace earlier ones where they overlap"),
+        )
+        .arg(
+            Arg::new("lang-detect")
+                .long("lang-detect")
+                .action(ArgAction::SetTrue)
+                .help("Detect each input's language; {langs} in --output-template uses it (needs the 'lang' feature)"),
+        )
+        .arg(
+            Arg::new("decode-entities")
+                .long("decode-entities")
+                .action(ArgAction::SetTrue)
+                .help("Decode HTML entities such as &amp; and &#233; in cue text"),
+        )
+        .arg(
+            Arg::new("per-block-encoding")
+                .long("per-block-encoding")
+                .action(ArgAction::SetTrue)
+                .help("Detect the encoding of each cue block on its own, to salvage files joined from parts in different encodings"),
+        )
+        .arg(
+            Arg::new("fix-mojibake")
+                .long("fix-mojibake")
+                .action(ArgAction::SetTrue)
+                .help("Repair cue text that is UTF-8 read as Windows-1252, such as Ã© for é, where it maps back cleanly"),
+        )
+        .arg(
+            Arg::new("normalize-speakers")
+                .long("normalize-speakers")
+                .value_name("DASH")
+                .value_parser(["hyphen", "hyphen-tight", "en-dash"])
+                .num_args(0..=1)
+                .default_missing_value("hyphen")
+                .help("Write dialogue dashes alike in all inputs, as '- text' (hyphen, the default), '-text' (hyphen-tight) or '– text' (en-dash), and speaker prefixes as 'NAME: text'"),
+        )
+        .arg(
+            Arg::new("speaker-case")
+                .long("speaker-case")
+                .value_parser(["upper", "title", "keep"])
+                .default_value("upper")
+                .requires("normalize-speakers")
+                .help("How --normalize-speakers writes speaker names: JOHN, John or as written"),
+        )
+        .arg(
+            Arg::new("join-cjk-lines")
+                .long("join-cjk-lines")
+                .action(ArgAction::SetTrue)
+                .help("Join the lines of each cue into one, with no space between Chinese or Japanese lines and a space between others; lines opening with a dialogue dash stay apart"),
+        )
+        .arg(
+            Arg::new("dedup-inputs")
+                .long("dedup-inputs")
+                .action(ArgAction::SetTrue)
+                .help("Load inputs with byte-identical content only once"),
+        )
+        .arg(
+            Arg::new("fail-fast")
+                .long("fail-fast")
+                .action(ArgAction::SetTrue)
+                .help("Stop at the first input that fails to load instead of listing every failure"),
+        )
+        .arg(
+            Arg::new("priority")
+                .long("priority")
+                .value_name("INPUTS")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("With --merge-strategy overwrite, input numbers from highest to lowest precedence, e.g. 1,3,2 (default: later inputs win)"),
+        )
+        .arg(
+            Arg::new("dedup")
+                .long("dedup")
+                .action(ArgAction::SetTrue)
+                .help("Collapse overlapping cues whose text differs only in casing, punctuation or spacing"),
+        )
+        .arg(
+            Arg::new("ass-rounding")
+                .long("ass-rounding")
+                .value_parser(["nearest", "floor", "ceil"])
+                .default_value("nearest")
+                .help("How ASS output rounds times to centiseconds; cues never collide or overlap because of it"),
+        )
+        .arg(
+            Arg::new("ms-sep")
+                .long("ms-sep")
+                .value_parser(["comma", "dot"])
+                .help("Separator before the milliseconds in SRT and VTT output [default: the format's own, comma for SRT, dot for VTT]"),
+        )
+        .arg(
+            Arg::new("vtt-hours")
+                .long("vtt-hours")
+                .value_parser(["always", "auto"])
+                .default_value("always")
+                .help("In VTT output, write the hour of every timestamp, or with auto leave it out when no cue reaches an hour"),
+        )
+        .arg(
+            Arg::new("keep-unknown-sections")
+                .long("keep-unknown-sections")
+                .action(ArgAction::SetTrue)
+                .help("Copy ASS sections the merge does not use, such as [Aegisub Project Garbage], into ASS output; the first input's wins"),
+        )
+        .arg(
+            Arg::new("keep-variant")
+                .long("keep-variant")
+                .value_parser(["first", "longest", "most-punct"])
+                .default_value("first")
+                .requires("dedup")
+                .help("Which text --dedup keeps: the earlier cue's, the longer one or the one with more punctuation"),
+        )
+        .arg(
+            Arg::new("intersect")
+                .long("intersect")
+                .action(ArgAction::SetTrue)
+                .help("Keep only cues that two or more inputs contributed to; needs --merge-strategy vertical, which stacks the cues of different inputs"),
+        )
+        .arg(
+            Arg::new("concat")
+                .long("concat")
+                .action(ArgAction::SetTrue)
+                .help("Append inputs end to end, shifting each part to start after the previous one ends"),
+        )
+        .arg(
+            Arg::new("no-renumber")
+                .long("no-renumber")
+                .action(ArgAction::SetTrue)
+                .requires("concat")
+                .help("Keep each part's SRT cue numbers, continuing after the highest number of the parts before it"),
+        )
+        .arg(
+            Arg::new("concat-gap")
+                .long("concat-gap")
+                .value_name("DURATION")
+                .value_parser(parse_duration)
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .allow_hyphen_values(true)
+                .requires("concat")
+                .help("Gap inserted between concatenated parts (e.g. 500ms, -1.2s, 00:00:02.500); a comma list sets one gap per boundary"),
+        )
+        .arg(
+            Arg::new("bundle-window")
+                .long("bundle-window")
+                .value_name("DURATION|FRAMESf")
+                .value_parser(parse_tolerance)
+                .help("With --merge-strategy vertical, stack cues whose starts fall within this window (default --tolerance, else 0 = exact match; '2f' = two frames at --fps)"),
+        )
+        .arg(
+            Arg::new("tolerance")
+                .long("tolerance")
+                .value_name("DURATION|FRAMESf")
+                .value_parser(parse_tolerance)
+                .help("Default window of --bundle-window, --collapse-gap and --snap-tolerance; each of those, when given, wins over it"),
+        )
+        .arg(
+            Arg::new("fps")
+                .long("fps")
+                .value_name("FPS")
+                .value_parser(parse_positive_number)
+                .help("Frame rate used to convert frame-based windows to milliseconds"),
+        )
+        .arg(
+            Arg::new("ignore-format-errors")
+                .long("ignore-format-errors")
+                .action(ArgAction::SetTrue)
+                .help("Retry with the other parser when the detected one fails or finds no cues"),
+        )
+        .arg(
+            Arg::new("scale")
+                .long("scale")
+                .value_name("FACTOR")
+                .value_parser(parse_positive_number)
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("Multiply all timestamps by FACTOR, before any offset; a comma list sets one factor per input"),
+        )
+        .arg(
+            Arg::new("reading-speed-warn")
+                .long("reading-speed-warn")
+                .value_name("CPS")
+                .value_parser(clap::value_parser!(f64))
+                .help("Warn about merged cues faster than CPS characters per second"),
+        )
+        .arg(
+            Arg::new("format-in")
+                .long("format-in")
+                .value_name("FORMAT")
+                .value_parser(["srt", "vtt", "ass"])
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("Parse inputs as FORMAT instead of detecting it; a comma list sets one format per input"),
+        )
+        .arg(
+            Arg::new("format-out")
+                .long("format-out")
+                .alias("format")
+                .value_name("FORMAT")
+                .value_parser(["srt", "vtt", "ass"])
+                .help("Write FORMAT whatever the output extension; by default .ass and .vtt outputs are ASS and WebVTT and anything else SRT")
+                .long_help("Write FORMAT whatever the output extension; by default .ass and .vtt outputs are ASS and WebVTT and anything else SRT.\n\nInputs may mix formats. Each input's tags are converted by its own format to the output's: ASS styling tags become <i>, <b>, <u>, <s> and <font color> in SRT and WebVTT, with positional tags such as {\\an8} kept and the rest dropped; SRT and WebVTT tags become ASS override tags; and WebVTT output drops <font> tags."),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .action(ArgAction::SetTrue)
+                .help("Allow the output path to overwrite one of the inputs"),
+        )
+        .arg(
+            Arg::new("filter")
+                .long("filter")
+                .value_name("REGEX")
+                .action(ArgAction::Append)
+                .help("Drop cues whose text matches REGEX (repeatable)"),
+        )
+        .arg(
+            Arg::new("filter-replace")
+                .long("filter-replace")
+                .value_name("REGEX=>REPLACEMENT")
+                .action(ArgAction::Append)
+                .help("Replace matches of REGEX in cue text (repeatable)"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Timeout for inputs fetched over HTTP(S)"),
+        )
+        .arg(
+            Arg::new("shift-first-cue-to")
+                .long("shift-first-cue-to")
+                .value_name("TIME")
+                .value_parser(parse_cli_timestamp)
+                .action(ArgAction::Append)
+                .help("Shift each input so its earliest cue starts at TIME (HH:MM:SS,mmm); repeat once per input for different times"),
+        )
+        .arg(
+            Arg::new("anchor")
+                .long("anchor")
+                .value_name("TIME1=TIME2")
+                .value_parser(parse_anchor)
+                .action(ArgAction::Append)
+                .conflicts_with("shift-first-cue-to")
+                .help("Offset input 2 so that TIME2 in it plays at TIME1 of input 1; repeat to anchor inputs 3, 4, ... the same way"),
+        )
+        .arg(
+            Arg::new("cut-range")
+                .long("cut-range")
+                .value_name("START-END[@N]")
+                .value_parser(parse_cut_range)
+                .action(ArgAction::Append)
+                .help("Remove a span cut from the video, e.g. 00:10:00-00:12:00: cues inside are dropped and later ones move earlier by its length; @N limits it to input N; repeatable"),
+        )
+        .arg(
+            Arg::new("romanize")
+                .long("romanize")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Stack an approximate Latin transliteration under each cue of input N (needs the 'translit' feature)"),
+        )
+        .arg(
+            Arg::new("final-newline")
+                .long("final-newline")
+                .value_name("yes|no")
+                .value_parser(["yes", "no"])
+                .default_value("yes")
+                .help("Whether SRT output ends with a blank line after the last cue"),
+        )
+        .arg(
+            Arg::new("hard-space")
+                .long("hard-space")
+                .value_parser(["space", "nbsp"])
+                .default_value("space")
+                .help("What ASS \\h hard spaces become in SRT and WebVTT output: a plain space or a no-break space (U+00A0)"),
+        )
+        .arg(
+            Arg::new("collapse-nbsp")
+                .long("collapse-nbsp")
+                .action(ArgAction::SetTrue)
+                .help("Write no-break spaces already in the inputs as plain spaces in SRT and WebVTT output"),
+        )
+        .arg(
+            Arg::new("index-width")
+                .long("index-width")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .help("Zero-pad SRT cue numbers to N digits, as in 0001, for tools that sort them as text"),
+        )
+        .arg(
+            Arg::new("diff-friendly")
+                .long("diff-friendly")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["ms-sep", "final-newline"])
+                .help("Write SRT and VTT in one layout for clean diffs: one blank line after every cue, no trailing spaces, zero-padded timestamps with the format's own millisecond separator"),
+        )
+        .arg(
+            Arg::new("layer-per-source")
+                .long("layer-per-source")
+                .action(ArgAction::SetTrue)
+                .help("In ASS output, put each input's events on its own layer (input 1 = layer 0)"),
+        )
+        .arg(
+            Arg::new("positions")
+                .long("positions")
+                .value_name("anN,...")
+                .value_parser(parse_alignment)
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("In ASS output, pin each input's lines to an alignment in input order, e.g. an8,an5,an2 for top, middle and bottom"),
+        )
+        .arg(
+            Arg::new("attribution")
+                .long("attribution")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Write a JSON map from output cue number to the input(s) it came from"),
+        )
+        .arg(
+            Arg::new("keep-styling-map")
+                .long("keep-styling-map")
+                .action(ArgAction::SetTrue)
+                .help("In SRT output, wrap text of bold/italic ASS styles in <b>/<i>"),
+        )
+        .arg(
+            Arg::new("max-lines")
+                .long("max-lines")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Split cues of more than N lines into consecutive cues that share its time evenly"),
+        )
+        .arg(
+            Arg::new("fill-gaps")
+                .long("fill-gaps")
+                .action(ArgAction::SetTrue)
+                .help("Keep each merged cue on screen until the next one starts; the last cue keeps its end"),
+        )
+        .arg(
+            Arg::new("fill-max")
+                .long("fill-max")
+                .value_name("DURATION")
+                .value_parser(parse_positive_duration)
+                .requires("fill-gaps")
+                .help("Extend no cue by more than this with --fill-gaps (e.g. 2s)"),
+        )
+        .arg(
+            Arg::new("silence-markers")
+                .long("silence-markers")
+                .value_name("GAP")
+                .value_parser(parse_positive_duration)
+                .help("Insert a placeholder cue spanning each gap between cues longer than GAP (e.g. 5s)"),
+        )
+        .arg(
+            Arg::new("silence-text")
+                .long("silence-text")
+                .value_name("TEXT")
+                .default_value("[silence]")
+                .requires("silence-markers")
+                .help("Text of the cues inserted by --silence-markers"),
+        )
+        .arg(
+            Arg::new("trim-whitespace-lines")
+                .long("trim-whitespace-lines")
+                .action(ArgAction::SetTrue)
+                .help("Drop empty lines inside a cue's text when writing it"),
+        )
+        .arg(
+            Arg::new("chunk-cues")
+                .long("chunk-cues")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Split the output into numbered files of at most N cues each"),
+        )
+        .arg(
+            Arg::new("chunk-duration")
+                .long("chunk-duration")
+                .value_name("DURATION")
+                .value_parser(parse_positive_duration)
+                .help("Split the output into numbered files spanning at most DURATION (e.g. 600s or 00:10:00) each"),
+        )
+        .arg(
+            Arg::new("rebase")
+                .long("rebase")
+                .value_name("LEAD_IN")
+                .num_args(0..=1)
+                .default_missing_value("0")
+                .value_parser(parse_non_negative_duration)
+                .help("Shift the output (or each chunk) so its first cue starts at LEAD_IN (default 0)"),
+        )
+        .arg(
+            Arg::new("collapse-consecutive")
+                .long("collapse-consecutive")
+                .action(ArgAction::SetTrue)
+                .help("Join back-to-back cues of an input that repeat the same text into one longer cue"),
+        )
+        .arg(
+            Arg::new("collapse-gap")
+                .long("collapse-gap")
+                .value_name("DURATION|FRAMESf")
+                .value_parser(parse_tolerance)
+                .default_value("100ms")
+                .hide_default_value(true)
+                .requires("collapse-consecutive")
+                .help("How far apart --collapse-consecutive lets repeated cues be [default: --tolerance, else 100ms]"),
+        )
+        .arg(
+            Arg::new("min-chars")
+                .long("min-chars")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .help("Drop cues with fewer than N characters once tags and surrounding spaces are removed"),
+        )
+        .arg(
+            Arg::new("sample-every")
+                .long("sample-every")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Keep only the first merged cue and every Nth after it, at their own times, to skim the pacing"),
+        )
+        .arg(
+            Arg::new("media-duration")
+                .long("media-duration")
+                .value_name("TIME")
+                .value_parser(parse_cli_timestamp)
+                .help("Warn about merged cues ending after TIME (HH:MM:SS,mmm), the length of the media"),
+        )
+        .arg(
+            Arg::new("clamp-to-media")
+                .long("clamp-to-media")
+                .action(ArgAction::SetTrue)
+                .requires("media-duration")
+                .help("Cut cues at --media-duration, dropping those starting at or after it"),
+        )
+        .arg(
+            Arg::new("strict-timestamps")
+                .long("strict-timestamps")
+                .action(ArgAction::SetTrue)
+                .help("Fail on the first cue that ends before it starts, starts before zero or starts before the cue above it"),
+        )
+        .arg(
+            Arg::new("strict-tolerance")
+                .long("strict-tolerance")
+                .value_name("DURATION|FRAMESf")
+                .value_parser(parse_tolerance)
+                .default_value("0ms")
+                .requires("strict-timestamps")
+                .help("How far before the cue above it --strict-timestamps lets a cue start"),
+        )
+        .arg(
+            Arg::new("fix-swapped")
+                .long("fix-swapped")
+                .action(ArgAction::SetTrue)
+                .help("Swap the start and end of cues that end before they start instead of only warning"),
+        )
+        .arg(
+            Arg::new("one-line")
+                .long("one-line")
+                .action(ArgAction::SetTrue)
+                .help("Write each SRT cue's text on a single line, for grep and awk"),
+        )
+        .arg(
+            Arg::new("one-line-separator")
+                .long("one-line-separator")
+                .value_name("SEP")
+                .default_value(" / ")
+                .requires("one-line")
+                .help("What joins the lines of a cue flattened by --one-line"),
+        )
+        .arg(
+            Arg::new("vocab")
+                .long("vocab")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Mark the words and phrases listed in FILE, one per line, wherever they appear as whole words, ignoring case"),
+        )
+        .arg(
+            Arg::new("vocab-wrap")
+                .long("vocab-wrap")
+                .value_name("FORMAT=TEMPLATE")
+                .action(ArgAction::Append)
+                .requires("vocab")
+                .help("How --vocab marks a word in srt, vtt or ass output, {} standing for the word [defaults: srt='<font color=\"#ffff00\">{}</font>', vtt='<b>{}</b>', ass='{\\c&H00FFFF&}{}{\\c}']"),
+        )
+        .arg(
+            Arg::new("style-from")
+                .long("style-from")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Give SRT and WebVTT cues the style and positioning of the ASS event in FILE that overlaps each the longest, for styled ASS output"),
+        )
+        .arg(
+            Arg::new("snap-to")
+                .long("snap-to")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Snap cue starts and ends to the nearest shot change listed in FILE, one timecode per line"),
+        )
+        .arg(
+            Arg::new("snap-tolerance")
+                .long("snap-tolerance")
+                .value_name("DURATION|FRAMESf")
+                .value_parser(parse_tolerance)
+                .default_value("250ms")
+                .hide_default_value(true)
+                .requires("snap-to")
+                .help("How far --snap-to may move a cue boundary; farther ones stay put [default: --tolerance, else 250ms]"),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["output", "output-template", "preview"])
+                .help("Only validate the inputs: print OK or FAIL for each and exit nonzero if any failed"),
+        )
+        .arg(
+            Arg::new("offset-preview")
+                .long("offset-preview")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("check")
+                .help("Print each input's first and last cue times before and after its scale and offset, then exit without writing"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::SetTrue)
+                .help("Print the detected format, encoding and cue count of each input"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .help("Print only errors and output asked for, such as --preview; no summary, notes or warnings"),
+        )
+        .arg(
+            Arg::new("max-output-bytes")
+                .long("max-output-bytes")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64))
+                .help("Abort without writing if an output file would be larger than BYTES"),
+        )
+        .arg(
+            Arg::new("header-comment")
+                .long("header-comment")
+                .action(ArgAction::SetTrue)
+                .help("Start the output with a comment naming the tool version, the time, the inputs and the options"),
+        )
+        .arg(
+            Arg::new("overlap-report")
+                .long("overlap-report")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Write a TSV of how much of each cue of input 1 the other inputs' cues cover, in ms and as a fraction, to diagnose sync"),
+        )
+        .arg(
+            Arg::new("merge-window-report")
+                .long("merge-window-report")
+                .action(ArgAction::SetTrue)
+                .help("Print to stderr which source cues each merged cue groups and why, to tune --bundle-window"),
+        )
+        .arg(
+            Arg::new("preview")
+                .long("preview")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .help("Print the first N merged cues to stderr; the output file is only written if given"),
+        )
+}
 
-    fn parse_ass(
-        &self,
-        content: &str,
-        subtitle: &mut Subtitle,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let dialogue_regex = Regex::new(
-            r"Dialogue:\s*\d+,(\d+:\d+:\d+\.\d+),(\d+:\d+:\d+\.\d+),.*?,.*?,.*?,.*?,.*?,.*?,(.*)",
-        )?;
+fn main() -> ExitCode {
+    let matches = match cli().try_get_matches() {
+        Ok(matches) => matches,
+        Err(e) if e.use_stderr() => {
+            e.print().ok();
+            return ExitCode::from(5);
+        }
+        Err(e) => e.exit(),
+    };
+    match run(&matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(
+                e.downcast_ref::<MergeError>()
+                    .map_or(1, MergeError::exit_code),
+            )
+        }
+    }
+}
 
-        for line in content.lines() {
-            if let Some(caps) = dialogue_regex.captures(line) {
-                let start_time_str = caps.get(1).map_or("", |m| m.as_str());
-                let time = NaiveTime::parse_from_str(start_time_str, "%H:%M:%S.%3f")?;
-                let timestamp =
-                    time.hour() as i64 * 3600 + time.minute() as i64 * 60 + time.second() as i64;
+/// The `stats` subcommand: parses one file and prints its statistics.
+fn stats(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let path = matches.get_one::<String>("file").unwrap();
+    let subtitle = submerger::parse_file(path)?;
+    let stats = CueStats::of(&subtitle)
+        .ok_or_else(|| MergeError::Validation(format!("'{}': no cues found", path)))?;
+    if matches.get_flag("json") {
+        print!("{}", stats.to_json());
+    } else {
+        print!("{}", stats);
+    }
+    Ok(())
+}
 
-                let text = caps.get(3).map_or("", |m| m.as_str()).to_string();
+/// The page `serve` hosts: a form for the merge options and a timeline of
+/// the cues `/cues.json` returns for them, one row per source.
+#[cfg(feature = "serve")]
+const PREVIEW_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>submerger preview</title>
+<style>
+body { font: 14px sans-serif; margin: 1em; }
+#timeline { position: relative; overflow-x: auto; border-top: 1px solid #ccc; }
+.cue { position: absolute; height: 3em; overflow: hidden; padding: 2px; box-sizing: border-box;
+       background: #def; border: 1px solid #79a; font-size: 12px; white-space: pre; }
+</style>
+</head>
+<body>
+<form id="options">
+Strategy <select name="strategy"><option>vertical</option><option>sequential</option><option>overwrite</option></select>
+Bundle window <input name="bundle-window" value="0ms" size="6">
+Offsets <input name="offset" value="0,0" size="12">
+<button>Merge</button> <span id="status"></span>
+</form>
+<div id="timeline"></div>
+<script>
+const form = document.getElementById("options");
+const pxPerSecond = 80;
+async function render(event) {
+  if (event) event.preventDefault();
+  const query = new URLSearchParams(new FormData(form));
+  const response = await fetch("/cues.json?" + query);
+  const status = document.getElementById("status");
+  if (!response.ok) { status.textContent = await response.text(); return; }
+  const { cues } = await response.json();
+  status.textContent = cues.length + " cues";
+  const timeline = document.getElementById("timeline");
+  timeline.innerHTML = "";
+  let rows = 1;
+  for (const cue of cues) {
+    const row = Math.min(...cue.sources, 9);
+    rows = Math.max(rows, row + 1);
+    const div = document.createElement("div");
+    div.className = "cue";
+    div.style.left = cue.start / 1000 * pxPerSecond + "px";
+    div.style.width = Math.max((cue.end - cue.start) / 1000 * pxPerSecond, 2) + "px";
+    div.style.top = row * 3.2 + "em";
+    div.title = cue.text;
+    div.textContent = cue.text;
+    timeline.appendChild(div);
+  }
+  timeline.style.height = rows * 3.2 + "em";
+}
+form.addEventListener("submit", render);
+render();
+</script>
+</body>
+</html>
+"#;
 
-                subtitle
-                    .dialogs
-                    .entry(timestamp)
-                    .and_modify(|existing| *existing = format!("{}\n{}", existing, text))
-                    .or_insert(text);
+/// Merges `inputs` with the options of a `/cues.json` query: `strategy`,
+/// `bundle-window` and `offset`, a comma list of millisecond offsets in
+/// input order.
+#[cfg(feature = "serve")]
+fn cues_json(inputs: &[String], query: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let mut strategy = MergeStrategy::Sequential;
+    let mut bundle_window = 0;
+    let mut offsets = Vec::new();
+    for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match name {
+            "strategy" => {
+                strategy = match value {
+                    "vertical" => MergeStrategy::Vertical,
+                    "overwrite" => MergeStrategy::Overwrite,
+                    "sequential" => MergeStrategy::Sequential,
+                    _ => {
+                        return Err(
+                            MergeError::Validation(format!("unknown strategy '{}'", value)).into(),
+                        )
+                    }
+                }
+            }
+            "bundle-window" => {
+                bundle_window = parse_duration(value).map_err(MergeError::Validation)?
+            }
+            "offset" => {
+                offsets = value
+                    .split(',')
+                    .map(parse_duration)
+                    .collect::<Result<_, _>>()
+                    .map_err(MergeError::Validation)?
             }
+            _ => {}
+        }
+    }
+
+    let mut merger = SubtitleMerger::builder()
+        .strategy(strategy)
+        .bundle_window(bundle_window)
+        .build();
+    let jobs: Vec<(&str, InputOptions)> = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let options = InputOptions {
+                offset: offsets.get(i).copied().unwrap_or(0),
+                ..InputOptions::default()
+            };
+            (input.as_str(), options)
+        })
+        .collect();
+    merger.add_all(&jobs)?;
+    Ok(merger.format_cues_json())
+}
+
+/// Answers one request: the page at `/` and the merge at `/cues.json`.
+#[cfg(feature = "serve")]
+fn serve_request(stream: &mut std::net::TcpStream, inputs: &[String]) -> std::io::Result<()> {
+    use std::io::BufRead;
+
+    let mut reader = std::io::BufReader::new(&*stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers are not needed, but are read so the client is not reset.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (status, content_type, body) = match path {
+        "/" => ("200 OK", "text/html", PREVIEW_PAGE.to_string()),
+        "/cues.json" => match cues_json(inputs, query) {
+            Ok(json) => ("200 OK", "application/json", json),
+            Err(e) => ("400 Bad Request", "text/plain", e.to_string()),
+        },
+        _ => ("404 Not Found", "text/plain", "Not found".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Hosts the merge preview on localhost until interrupted. Every request
+/// re-reads the inputs, so edits to them show on the next render.
+#[cfg(feature = "serve")]
+fn serve(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let inputs: Vec<String> = matches
+        .get_many::<String>("inputs")
+        .unwrap()
+        .cloned()
+        .collect();
+    let port = *matches.get_one::<u16>("port").unwrap();
+    // Inputs that do not load fail here rather than on every request.
+    cues_json(&inputs, "")?;
+    let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| MergeError::Io(format!("Cannot listen on port {}: {}", port, e)))?;
+    eprintln!(
+        "Serving the merge preview at http://{}/",
+        listener.local_addr()?
+    );
+    for stream in listener.incoming() {
+        let result = stream.and_then(|mut stream| serve_request(&mut stream, &inputs));
+        if let Err(e) = result {
+            eprintln!("Warning: request failed: {}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "serve"))]
+fn serve(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    Err(
+        MergeError::Validation("Cannot serve: built without the 'serve' feature".to_string())
+            .into(),
+    )
+}
+
+fn run(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    match matches.subcommand() {
+        Some(("stats", matches)) => return stats(matches),
+        Some(("serve", matches)) => return serve(matches),
+        _ => {}
+    }
+    let mut inputs = vec![
+        matches.get_one::<String>("input1").unwrap(),
+        matches.get_one::<String>("input2").unwrap(),
+    ];
+    inputs.extend(
+        matches
+            .get_many::<String>("add-input")
+            .into_iter()
+            .flatten(),
+    );
+    if let Some(selectors) = matches.get_many::<String>("keep-only-sources") {
+        inputs = keep_only_sources(inputs, &selectors.collect::<Vec<_>>())?;
+    }
+    let output = matches.get_one::<String>("output");
+
+    let fps = matches.get_one::<f64>("fps").copied();
+    let strategy = match matches
+        .get_one::<String>("merge-strategy")
+        .unwrap()
+        .as_str()
+    {
+        "vertical" => MergeStrategy::Vertical,
+        "overwrite" => MergeStrategy::Overwrite,
+        _ => MergeStrategy::Sequential,
+    };
+    if matches.get_flag("intersect") && !matches!(strategy, MergeStrategy::Vertical) {
+        return Err(MergeError::Validation(
+            "--intersect needs --merge-strategy vertical: other strategies never stack cues of different inputs, so nothing would be kept".to_string(),
+        )
+        .into());
+    }
+    let ass_rounding = match matches.get_one::<String>("ass-rounding").unwrap().as_str() {
+        "floor" => AssRounding::Floor,
+        "ceil" => AssRounding::Ceil,
+        _ => AssRounding::Nearest,
+    };
+    let speaker_style = matches
+        .get_one::<String>("normalize-speakers")
+        .map(|dash| SpeakerStyle {
+            dash: match dash.as_str() {
+                "hyphen-tight" => "-",
+                "en-dash" => "– ",
+                _ => "- ",
+            },
+            case: match matches.get_one::<String>("speaker-case").unwrap().as_str() {
+                "title" => SpeakerCase::Title,
+                "keep" => SpeakerCase::Keep,
+                _ => SpeakerCase::Upper,
+            },
+        });
+    let keep_variant = match matches.get_one::<String>("keep-variant").unwrap().as_str() {
+        "longest" => KeepVariant::Longest,
+        "most-punct" => KeepVariant::MostPunct,
+        _ => KeepVariant::First,
+    };
+    let bundle_window = window_ms(matches, "bundle-window", fps)?.unwrap_or(0);
+    let vocab = match matches.get_one::<PathBuf>("vocab") {
+        Some(path) => {
+            let wraps: Vec<&String> = matches
+                .get_many::<String>("vocab-wrap")
+                .into_iter()
+                .flatten()
+                .collect();
+            Some(read_vocab(path, &wraps)?)
         }
+        None => None,
+    };
+    let style_from = match matches.get_one::<PathBuf>("style-from") {
+        Some(path) => Some(read_style_source(path)?),
+        None => None,
+    };
+    let snap_points = match matches.get_one::<PathBuf>("snap-to") {
+        Some(path) => read_shot_changes(path)?,
+        None => Vec::new(),
+    };
+    let snap_tolerance = window_ms(matches, "snap-tolerance", fps)?.unwrap();
+    let collapse_gap = window_ms(matches, "collapse-gap", fps)?.unwrap();
+    let strict_tolerance = matches
+        .get_one::<Tolerance>("strict-tolerance")
+        .unwrap()
+        .to_ms(fps)
+        .map_err(MergeError::Validation)?;
+    let concat_gaps: Vec<i64> = matches
+        .get_many::<i64>("concat-gap")
+        .map(|gaps| gaps.copied().collect())
+        .unwrap_or_default();
+    if concat_gaps.len() > 1 && concat_gaps.len() != inputs.len() - 1 {
+        return Err(MergeError::Validation(format!(
+            "--concat-gap expects 1 or {} values, got {}",
+            inputs.len() - 1,
+            concat_gaps.len()
+        ))
+        .into());
+    }
 
-        Ok(())
+    let positions = per_input::<u8>(matches, "positions", inputs.len())?;
+    let priority: Vec<usize> = matches
+        .get_many::<u64>("priority")
+        .map(|inputs| inputs.map(|&n| n as usize - 1).collect())
+        .unwrap_or_default();
+    if !priority.is_empty() {
+        let mut sorted = priority.clone();
+        sorted.sort_unstable();
+        if sorted != (0..inputs.len()).collect::<Vec<_>>() {
+            return Err(MergeError::Validation(format!(
+                "--priority must list each input number from 1 to {} once",
+                inputs.len()
+            ))
+            .into());
+        }
     }
 
-    fn merge(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut timestamps: Vec<i64> = self
-            .subtitles
+    let mut builder = SubtitleMerger::builder()
+        .output(output.map_or("", |path| path.as_str()))
+        .strategy(strategy)
+        .bundle_window(bundle_window)
+        .priority(priority)
+        .rescale_positions(!matches.get_flag("keep-positions"))
+        .include_comments(matches.get_flag("include-comments"))
+        .ignore_format_errors(matches.get_flag("ignore-format-errors"))
+        .reading_speed_limit(matches.get_one::<f64>("reading-speed-warn").copied())
+        .force(matches.get_flag("force"))
+        .layer_per_source(matches.get_flag("layer-per-source"))
+        .positions(positions.unwrap_or_default())
+        .concat(matches.get_flag("concat"), concat_gaps)
+        .renumber(!matches.get_flag("no-renumber"))
+        .ass_rounding(ass_rounding)
+        .max_output_bytes(matches.get_one::<u64>("max-output-bytes").copied())
+        .ms_separator(matches.get_one::<String>("ms-sep").map(|separator| {
+            if separator == "dot" {
+                '.'
+            } else {
+                ','
+            }
+        }))
+        .vtt_omit_hours(matches.get_one::<String>("vtt-hours").unwrap() == "auto")
+        .attribution(matches.get_one::<PathBuf>("attribution").cloned())
+        .keep_styling_map(matches.get_flag("keep-styling-map"))
+        .intersect(matches.get_flag("intersect"))
+        .dedup(matches.get_flag("dedup").then_some(keep_variant))
+        .decode_entities(matches.get_flag("decode-entities"))
+        .fix_mojibake(matches.get_flag("fix-mojibake"))
+        .per_block_encoding(matches.get_flag("per-block-encoding"))
+        .speaker_style(speaker_style)
+        .join_cjk_lines(matches.get_flag("join-cjk-lines"))
+        .lang_detect(matches.get_flag("lang-detect"))
+        .dedup_inputs(matches.get_flag("dedup-inputs"))
+        .fail_fast(matches.get_flag("fail-fast"))
+        .max_lines(matches.get_one::<u64>("max-lines").map(|&n| n as usize))
+        .silence_markers(
+            matches.get_one::<i64>("silence-markers").copied(),
+            matches.get_one::<String>("silence-text").unwrap(),
+        )
+        .fill_gaps(
+            matches.get_flag("fill-gaps"),
+            matches.get_one::<i64>("fill-max").copied(),
+        )
+        .trim_whitespace_lines(matches.get_flag("trim-whitespace-lines"))
+        .chunking(
+            matches.get_one::<u64>("chunk-cues").map(|&n| n as usize),
+            matches.get_one::<i64>("chunk-duration").copied(),
+        )
+        .rebase(matches.get_one::<i64>("rebase").copied())
+        .snap_to(snap_points, snap_tolerance)
+        .fix_swapped(matches.get_flag("fix-swapped"))
+        .quiet(matches.get_flag("quiet"))
+        .keep_unknown_sections(matches.get_flag("keep-unknown-sections"))
+        .vocab(vocab)
+        .style_from(style_from)
+        .media_duration(
+            matches.get_one::<i64>("media-duration").copied(),
+            matches.get_flag("clamp-to-media"),
+        )
+        .collapse_consecutive(
+            matches
+                .get_flag("collapse-consecutive")
+                .then_some(collapse_gap),
+        )
+        .strict_timestamps(
+            matches
+                .get_flag("strict-timestamps")
+                .then_some(strict_tolerance),
+        )
+        .one_line(matches.get_flag("one-line").then(|| {
+            matches
+                .get_one::<String>("one-line-separator")
+                .unwrap()
+                .as_str()
+        }))
+        .final_newline(matches.get_one::<String>("final-newline").unwrap() == "yes")
+        .diff_friendly(matches.get_flag("diff-friendly"))
+        .output_format(matches.get_one::<String>("format-out").cloned())
+        .hard_space(
+            match matches.get_one::<String>("hard-space").unwrap().as_str() {
+                "nbsp" => '\u{a0}',
+                _ => ' ',
+            },
+            matches.get_flag("collapse-nbsp"),
+        )
+        .index_width(
+            matches
+                .get_one::<usize>("index-width")
+                .copied()
+                .unwrap_or(0),
+        )
+        .min_chars(matches.get_one::<usize>("min-chars").copied())
+        .sample_every(matches.get_one::<u64>("sample-every").map(|&n| n as usize))
+        .timeout(
+            matches
+                .get_one::<u64>("timeout")
+                .map(|&seconds| std::time::Duration::from_secs(seconds)),
+        );
+    for filter in matches.get_many::<String>("filter").into_iter().flatten() {
+        builder =
+            builder.filter(Regex::new(filter).map_err(|e| MergeError::Validation(e.to_string()))?);
+    }
+    for rule in matches
+        .get_many::<String>("filter-replace")
+        .into_iter()
+        .flatten()
+    {
+        let (pattern, replacement) = rule.split_once("=>").ok_or_else(|| {
+            MergeError::Validation(format!(
+                "--filter-replace expects REGEX=>REPLACEMENT, got '{}'",
+                rule
+            ))
+        })?;
+        builder = builder.replacement(
+            Regex::new(pattern).map_err(|e| MergeError::Validation(e.to_string()))?,
+            replacement,
+        );
+    }
+    let mut merger = builder.build();
+
+    let scales = per_input(matches, "scale", inputs.len())?;
+    let formats = per_input::<String>(matches, "format-in", inputs.len())?;
+    let first_cue_times = per_input::<i64>(matches, "shift-first-cue-to", inputs.len())?;
+    let anchors: Vec<(i64, i64)> = matches
+        .get_many::<(i64, i64)>("anchor")
+        .map(|anchors| anchors.copied().collect())
+        .unwrap_or_default();
+    if anchors.len() >= inputs.len() {
+        return Err(MergeError::Validation(format!(
+            "--anchor expects at most {} values, one per input after the first, got {}",
+            inputs.len() - 1,
+            anchors.len()
+        ))
+        .into());
+    }
+    let cuts: Vec<(i64, i64, Option<usize>)> = matches
+        .get_many::<(i64, i64, Option<usize>)>("cut-range")
+        .map(|cuts| cuts.copied().collect())
+        .unwrap_or_default();
+    if let Some(&(_, _, Some(n))) = cuts
+        .iter()
+        .find(|(_, _, input)| input.is_some_and(|n| n > inputs.len()))
+    {
+        return Err(MergeError::Validation(format!(
+            "--cut-range expects an input number from 1 to {}, got {}",
+            inputs.len(),
+            n
+        ))
+        .into());
+    }
+    let romanize = match matches.get_one::<u64>("romanize") {
+        Some(&n) if n as usize > inputs.len() => {
+            return Err(MergeError::Validation(format!(
+                "--romanize expects an input number from 1 to {}, got {}",
+                inputs.len(),
+                n
+            ))
+            .into())
+        }
+        Some(&n) => Some(n as usize - 1),
+        None => None,
+    };
+    let mut jobs = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        let mut options = InputOptions::default();
+        if let Some(scales) = &scales {
+            options.scale = scales[i];
+        }
+        if let Some(formats) = &formats {
+            options.format = Some(formats[i].clone());
+        }
+        if let Some(times) = &first_cue_times {
+            options.first_cue_at = Some(times[i]);
+        }
+        // Both anchor times are as written, so each goes through its own
+        // input's scale before they are lined up.
+        if let Some(&(first, other)) = i.checked_sub(1).and_then(|k| anchors.get(k)) {
+            let scale_of = |input: usize| scales.as_ref().map_or(1.0, |scales| scales[input]);
+            options.offset =
+                (first as f64 * scale_of(0) - other as f64 * scale_of(i)).round() as i64;
+        }
+        options.romanize = romanize == Some(i);
+        options.cuts = cuts
             .iter()
-            .flat_map(|sub| sub.dialogs.keys().cloned())
+            .filter(|(_, _, input)| input.is_none_or(|n| n == i + 1))
+            .map(|&(start, end, _)| (start, end))
             .collect();
-        timestamps.sort_unstable();
-        timestamps.dedup();
-
-        let mut output_lines = Vec::new();
-        let mut count = 1;
-
-        for timestamp in timestamps {
-            for subtitle in &self.subtitles {
-                if let Some(dialog) = subtitle.dialogs.get(&timestamp) {
-                    let line = format!("{}\n{}\n", count, dialog);
-                    output_lines.push(line);
-                    count += 1;
-                }
+        jobs.push((input.as_str(), options));
+    }
+    if matches.get_flag("check") {
+        let results = merger.check(&jobs);
+        let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+        for (path, result) in &results {
+            match result {
+                Ok(source) => println!("OK    {}", source),
+                Err(e) => println!("FAIL  '{}': {}", path, e),
             }
         }
+        if failed > 0 {
+            return Err(MergeError::Validation(format!(
+                "{} of {} inputs failed the check",
+                failed,
+                results.len()
+            ))
+            .into());
+        }
+        return Ok(());
+    }
 
-        let mut output_file = File::create(&self.output_path)?;
-        output_file.write_all(output_lines.join("\n").as_bytes())?;
+    if matches.get_flag("offset-preview") {
+        eprint!("{}", merger.offset_preview(&jobs)?);
+        return Ok(());
+    }
 
-        println!("'{}' created successfully.", self.output_path);
-        Ok(())
+    merger.add_all(&jobs)?;
+
+    // Templates may name the languages, which are only known once loaded.
+    let output = match matches.get_one::<String>("output-template") {
+        Some(template) => {
+            let path = expand_output_template(template, &merger.sources())
+                .map_err(MergeError::Validation)?;
+            merger.set_output(&path);
+            Some(path)
+        }
+        None => output.cloned(),
+    };
+
+    if matches.get_flag("header-comment") {
+        merger.set_header_comment(header_comment(matches, &merger.sources()));
     }
-}
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let matches = Command::new("subtitle-merger")
-        .about("Merge subtitle files")
-        .arg(Arg::new("input1").index(1).required(true))
-        .arg(Arg::new("input2").index(2).required(true))
-        .arg(Arg::new("output").index(3).required(true))
-        .get_matches();
+    if matches.get_flag("verbose") {
+        for source in merger.sources() {
+            eprintln!("{}", source);
+        }
+    }
 
-    let input1 = matches.get_one::<String>("input1").unwrap();
-    let input2 = matches.get_one::<String>("input2").unwrap();
-    let output = matches.get_one::<String>("output").unwrap();
+    if let Some(&count) = matches.get_one::<usize>("preview") {
+        eprint!(
+            "{}",
+            merger.format_preview(count, std::io::stderr().is_terminal())
+        );
+    }
+
+    if let Some(path) = matches.get_one::<PathBuf>("overlap-report") {
+        File::create(path)
+            .and_then(|mut file| file.write_all(merger.format_overlap_report().as_bytes()))
+            .map_err(|e| MergeError::Io(format!("Cannot write '{}': {}", path.display(), e)))?;
+    }
+
+    if matches.get_flag("merge-window-report") {
+        eprint!("{}", merger.format_window_report());
+    }
 
-    let mut merger = SubtitleMerger::new(output);
-    merger.add(input1)?;
-    merger.add(input2)?;
-    merger.merge()?;
+    if output.is_some() {
+        let summary = merger.merge()?;
+        merger.note(summary.to_string());
+        if let Some(limit) = matches.get_one::<f64>("reading-speed-warn") {
+            for (index, cps) in &summary.fast_cues {
+                merger.note(format!(
+                    "Warning: cue {} reads at {:.1} cps, {:.1} over the {} cps limit",
+                    index,
+                    cps,
+                    cps - limit,
+                    limit
+                ));
+            }
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,3342 @@
+//! Merging parsed inputs into one subtitle: [`SubtitleMerger`], set up
+//! with [`SubtitleMerger::builder`], loads and transforms each input and
+//! writes the merged cues as SRT, WebVTT or ASS.
+
+use crate::{
+    decode, decode_blocks, detect_format, format_timestamp, insert_dialog, json_string, parse_str,
+    Dialog, MergeError, StyleLine, Subtitle, ASS_DEFAULT_STYLE, ASS_STYLE_COLUMNS,
+};
+use regex::Regex;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// What was detected about an input when it was added.
+#[derive(Debug, Clone)]
+pub struct SourceInfo {
+    pub path: String,
+    /// `srt`, `vtt` or `ass`.
+    pub format: String,
+    /// The encoding the input was decoded from.
+    pub encoding: &'static str,
+    /// Cues and ASS `Comment:` events together.
+    pub cue_count: usize,
+    /// The detected language, if any.
+    pub language: Option<String>,
+}
+
+impl From<&Subtitle> for SourceInfo {
+    fn from(subtitle: &Subtitle) -> Self {
+        SourceInfo {
+            path: subtitle.path.clone(),
+            format: subtitle.format.clone(),
+            encoding: subtitle.encoding,
+            cue_count: subtitle.dialogs.len() + subtitle.comments.len(),
+            language: subtitle.language.clone(),
+        }
+    }
+}
+
+impl fmt::Display for SourceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}': {}, {}, {} cues",
+            self.path, self.format, self.encoding, self.cue_count
+        )?;
+        match &self.language {
+            Some(language) => write!(f, ", {}", language),
+            None => Ok(()),
+        }
+    }
+}
+
+/// How cues from different inputs are combined by `merge`.
+#[derive(Debug, Clone, Copy)]
+pub enum MergeStrategy {
+    /// Stack cues starting within the bundle window into one cue.
+    Vertical,
+    /// Write every cue as its own numbered entry.
+    Sequential,
+    /// Later inputs replace earlier ones where their cues overlap.
+    Overwrite,
+}
+
+/// How ASS output rounds millisecond times to its centiseconds.
+#[derive(Debug, Clone, Copy)]
+pub enum AssRounding {
+    Nearest,
+    Floor,
+    Ceil,
+}
+
+impl AssRounding {
+    fn centiseconds(self, ms: i64) -> i64 {
+        match self {
+            AssRounding::Nearest => (ms + 5).div_euclid(10),
+            AssRounding::Floor => ms.div_euclid(10),
+            AssRounding::Ceil => (ms + 9).div_euclid(10),
+        }
+    }
+}
+
+/// Which of two near-identical cues `--dedup` keeps.
+#[derive(Debug, Clone, Copy)]
+pub enum KeepVariant {
+    /// The one that comes first in time, then in input order.
+    First,
+    /// The one with more characters.
+    Longest,
+    /// The one with more punctuation marks.
+    MostPunct,
+}
+
+impl KeepVariant {
+    /// Whether `candidate` should replace `kept`; ties keep `kept`.
+    fn prefers(self, kept: &str, candidate: &str) -> bool {
+        let punctuation = |text: &str| text.chars().filter(|c| c.is_ascii_punctuation()).count();
+        match self {
+            KeepVariant::First => false,
+            KeepVariant::Longest => candidate.chars().count() > kept.chars().count(),
+            KeepVariant::MostPunct => punctuation(candidate) > punctuation(kept),
+        }
+    }
+}
+
+/// Options applied to a single input while it is added.
+#[derive(Debug, Clone)]
+pub struct InputOptions {
+    /// Multiplies every timestamp of the input; finite and above zero.
+    /// Transforms compose as `scale * t + offset`, so the scale is applied
+    /// before any offset.
+    pub scale: f64,
+    /// Added to every timestamp after the scale (ms).
+    pub offset: i64,
+    /// Parses the input as this format instead of detecting it.
+    pub format: Option<String>,
+    /// Shifts the whole input so its earliest cue starts at this time (ms).
+    pub first_cue_at: Option<i64>,
+    /// Stacks a Latin transliteration under each cue's text.
+    pub romanize: bool,
+    /// Spans removed from the video, each `(start, end)` in ms: cues inside
+    /// are dropped and later ones move earlier by the span's length.
+    pub cuts: Vec<(i64, i64)>,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            scale: 1.0,
+            offset: 0,
+            format: None,
+            first_cue_at: None,
+            romanize: false,
+            cuts: Vec::new(),
+        }
+    }
+}
+
+pub struct SubtitleMerger {
+    subtitles: Vec<Subtitle>,
+    output_path: PathBuf,
+    play_res: Option<(u32, u32)>,
+    rescale_positions: bool,
+    include_comments: bool,
+    strategy: MergeStrategy,
+    /// Grouping window in ms for the vertical strategy.
+    bundle_window: i64,
+    /// Source indices from highest to lowest precedence for the overwrite
+    /// strategy; empty means later inputs win.
+    priority: Vec<usize>,
+    ignore_format_errors: bool,
+    reading_speed_limit: Option<f64>,
+    force: bool,
+    filters: Vec<Regex>,
+    replacements: Vec<(Regex, String)>,
+    timeout: Option<std::time::Duration>,
+    /// End SRT output with a blank line after the last cue.
+    final_newline: bool,
+    layer_per_source: bool,
+    /// ASS `\an` alignment for each source's lines, in input order.
+    positions: Vec<u8>,
+    /// Treat inputs as consecutive parts of one file instead of parallel tracks.
+    concat: bool,
+    /// Gap in ms inserted at each part boundary; a single value applies to all.
+    concat_gaps: Vec<i64>,
+    attribution_path: Option<PathBuf>,
+    keep_styling_map: bool,
+    /// Keep only merged cues that at least two sources contributed to.
+    intersect: bool,
+    lang_detect: bool,
+    /// Decode HTML entities in cue text while loading.
+    decode_entities: bool,
+    /// Load inputs with identical content only once.
+    dedup_inputs: bool,
+    /// Stop at the first input that fails to load instead of reporting all.
+    fail_fast: bool,
+    /// Split cues taller than this many lines.
+    max_lines: Option<usize>,
+    /// Mark gaps longer than this many ms with a placeholder cue.
+    silence_gap: Option<i64>,
+    silence_text: String,
+    /// Drop empty lines inside a cue's text when writing it.
+    trim_whitespace_lines: bool,
+    /// Split the output into files of at most this many cues.
+    chunk_cues: Option<usize>,
+    /// Split the output into files spanning at most this many ms.
+    chunk_duration: Option<i64>,
+    /// Shift the output, or each chunk of it, so the first cue starts at
+    /// this lead-in in ms.
+    rebase: Option<i64>,
+    /// Sorted shot-change times in ms that cue boundaries snap to.
+    snap_points: Vec<i64>,
+    /// How far in ms a boundary may move to reach a shot change.
+    snap_tolerance: i64,
+    /// Flatten each SRT cue's text onto one line, joining lines with this.
+    one_line: Option<String>,
+    /// Swap the times of cues that end before they start.
+    fix_swapped: bool,
+    /// Collapse overlapping cues that differ only in casing, punctuation or
+    /// spacing, keeping this variant.
+    dedup: Option<KeepVariant>,
+    /// Number SRT output 1, 2, 3, ...; off keeps each concatenated part's
+    /// own numbers, offset past the highest number of the parts before it.
+    renumber: bool,
+    ass_rounding: AssRounding,
+    /// Provenance lines written as a comment at the top of each output.
+    header_comment: Vec<String>,
+    /// Refuse to write an output file larger than this.
+    max_output_bytes: Option<u64>,
+    /// Separator before the milliseconds of SRT and VTT timestamps; `None`
+    /// keeps each format's own, a comma for SRT and a dot for VTT.
+    ms_separator: Option<char>,
+    /// Leave the hour out of VTT timestamps when no cue reaches an hour.
+    vtt_omit_hours: bool,
+    /// Fail on the first impossible time in an input, letting a cue start
+    /// at most this many ms before the previous one.
+    strict_timestamps: Option<i64>,
+    /// Print nothing but errors and the output explicitly asked for.
+    quiet: bool,
+    /// Join back-to-back cues of an input with the same text when at most
+    /// this many ms apart.
+    collapse_gap: Option<i64>,
+    /// Write the ASS sections of the inputs that the merge does not use,
+    /// such as `[Aegisub Project Garbage]`, into ASS output.
+    keep_unknown_sections: bool,
+    vocab: Option<Vocab>,
+    /// Length of the media in ms; cues ending later are reported.
+    media_duration: Option<i64>,
+    /// Cut cues at `media_duration` instead of only reporting them.
+    clamp_to_media: bool,
+    /// Write SRT and VTT cue text without trailing spaces or blank lines.
+    diff_friendly: bool,
+    /// `srt`, `vtt` or `ass`, overriding the output extension.
+    output_format: Option<String>,
+    /// Zero-pads SRT cue numbers to this many digits; 0 leaves them bare.
+    index_width: usize,
+    /// Repair cue text that is UTF-8 decoded as Windows-1252, like `Ã©`.
+    fix_mojibake: bool,
+    /// Decode each cue block on its own, for files joined from parts in
+    /// different encodings.
+    per_block_encoding: bool,
+    /// What ASS `\h` hard spaces become in SRT and WebVTT output.
+    hard_space: char,
+    /// Write no-break spaces in SRT and WebVTT cue text as plain spaces.
+    collapse_nbsp: bool,
+    /// Keep only the first merged cue and every Nth after it.
+    sample_every: Option<usize>,
+    /// Rewrite dialogue dashes and speaker names alike in every input.
+    speaker_style: Option<SpeakerStyle>,
+    /// Drop cues with fewer visible characters than this, spaces around
+    /// the text not counting.
+    min_chars: Option<usize>,
+    /// Join the lines of each cue into one, without a space between CJK
+    /// lines.
+    join_cjk_lines: bool,
+    /// Extend each cue's end to the next cue's start.
+    fill_gaps: bool,
+    /// With `fill_gaps`, extend no cue by more than this many ms.
+    fill_max: Option<i64>,
+    /// The ASS script whose styles and positioning SRT and WebVTT cues
+    /// take on, matched by time.
+    style_from: Option<Subtitle>,
+}
+
+#[derive(Debug, Clone)]
+struct CuePart {
+    source: usize,
+    /// Start of the source cue, which may differ from the merged cue's.
+    start: i64,
+    text: String,
+    style: Option<String>,
+    index: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct MergedCue {
+    start: i64,
+    end: i64,
+    /// One part per contributing source, stacked top to bottom.
+    parts: Vec<CuePart>,
+    /// Set for ASS `Comment:` events, which are only kept in ASS output.
+    comment: bool,
+    /// Placeholder text of a silence marker, which has no parts.
+    marker: Option<String>,
+}
+
+impl MergedCue {
+    fn text(&self) -> String {
+        if let Some(marker) = &self.marker {
+            return marker.clone();
+        }
+        self.parts
+            .iter()
+            .map(|part| part.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+static HTML_FORMAT_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<(/?)(b|i|u|s|font)\b[^>]*>").unwrap());
+
+/// Joins stacked texts with line breaks so that no part's styling bleeds
+/// into the next: HTML tags a part leaves open are closed at its end, and a
+/// part after one with ASS override tags, which last until the end of the
+/// event, starts with a `{\r}` reset.
+fn stack_parts(parts: impl IntoIterator<Item = String>) -> String {
+    let mut overridden = false;
+    let mut stacked = Vec::new();
+    for mut text in parts {
+        let mut open: Vec<String> = Vec::new();
+        for caps in HTML_FORMAT_TAG.captures_iter(&text) {
+            let tag = caps[2].to_lowercase();
+            if caps[1].is_empty() {
+                open.push(tag);
+            } else if let Some(i) = open.iter().rposition(|name| *name == tag) {
+                open.remove(i);
+            }
+        }
+        for tag in open.iter().rev() {
+            text.push_str(&format!("</{}>", tag));
+        }
+        if overridden {
+            text.insert_str(0, "{\\r}");
+        }
+        overridden |= text.contains("{\\");
+        stacked.push(text);
+    }
+    stacked.join("\n")
+}
+
+/// How `--normalize-speakers` writes a `NAME:` speaker prefix.
+#[derive(Debug, Clone, Copy)]
+pub enum SpeakerCase {
+    /// `JOHN: Hello.`
+    Upper,
+    /// `John: Hello.`
+    Title,
+    /// The name as written, only the spacing around the colon fixed.
+    Keep,
+}
+
+/// `--normalize-speakers`: the one way every input writes dialogue dashes
+/// and speaker names.
+#[derive(Debug, Clone, Copy)]
+pub struct SpeakerStyle {
+    /// What a line opening with any dash starts with instead: `- `, `-`
+    /// or `– `.
+    pub dash: &'static str,
+    pub case: SpeakerCase,
+}
+
+impl SpeakerCase {
+    fn apply(self, name: &str) -> String {
+        match self {
+            SpeakerCase::Upper => name.to_uppercase(),
+            SpeakerCase::Title => name
+                .split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map_or_else(String::new, |first| {
+                        first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect()
+                    })
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            SpeakerCase::Keep => name.to_string(),
+        }
+    }
+}
+
+impl SpeakerStyle {
+    /// Rewrites each line, across `\n` and ASS `\N` breaks, that opens with
+    /// a dialogue dash or a speaker prefix, after any leading tags. A
+    /// speaker prefix is up to three capitalized words and a colon.
+    fn normalize(&self, text: &str) -> String {
+        static DIALOGUE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^((?:\{[^}]*\}|<[^>]*>)*)\s*([-‐‑–—]+\s*)?(.*)$").unwrap()
+        });
+        static SPEAKER_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^(\p{Lu}[\p{L}'.]*(?: [\p{Lu}\d][\p{L}\d'.]*){0,2})\s*:(\s*)(.*)$")
+                .unwrap()
+        });
+        let normalize_line = |line: &str| -> String {
+            let caps = DIALOGUE_LINE.captures(line).unwrap();
+            let dashed = caps.get(2).is_some();
+            // `Act 2:30` or `http://` right after the colon are not speech.
+            let speaker = SPEAKER_PREFIX.captures(&caps[3]).filter(|speaker| {
+                !speaker[2].is_empty()
+                    || !speaker[3].starts_with(|c: char| c.is_ascii_digit() || c == '/')
+            });
+            if !dashed && speaker.is_none() {
+                return line.to_string();
+            }
+            let text = match speaker {
+                Some(speaker) => format!("{}: {}", self.case.apply(&speaker[1]), &speaker[3]),
+                None => caps[3].to_string(),
+            };
+            format!(
+                "{}{}{}",
+                &caps[1],
+                if dashed { self.dash } else { "" },
+                text
+            )
+        };
+        text.split('\n')
+            .map(|line| {
+                line.split("\\N")
+                    .map(normalize_line)
+                    .collect::<Vec<_>>()
+                    .join("\\N")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// `--vocab`: the words to mark in cue text and the template each output
+/// format wraps them in, with `{}` standing for the word as written.
+pub struct Vocab {
+    /// Matches a listed word or phrase as a whole word, ignoring case, or
+    /// else a whole tag, so words inside tags are never marked.
+    pattern: Regex,
+    /// The templates of SRT, WebVTT and ASS output.
+    pub srt: String,
+    pub vtt: String,
+    pub ass: String,
+}
+
+impl Vocab {
+    /// Marks `words` with the default template of each format: yellow in
+    /// SRT and ASS, bold in WebVTT.
+    pub fn new(words: &[String]) -> Vocab {
+        let mut words: Vec<String> = words.iter().map(|word| regex::escape(word)).collect();
+        // Longer entries first, so a phrase wins over a word inside it.
+        words.sort_by_key(|word| std::cmp::Reverse(word.len()));
+        let pattern = format!(r"(?i)\{{[^}}]*\}}|<[^>]*>|\b({})\b", words.join("|"));
+        Vocab {
+            pattern: Regex::new(&pattern).unwrap(),
+            srt: "<font color=\"#ffff00\">{}</font>".to_string(),
+            vtt: "<b>{}</b>".to_string(),
+            ass: "{\\c&H00FFFF&}{}{\\c}".to_string(),
+        }
+    }
+
+    fn mark(&self, text: &str, template: &str) -> String {
+        self.pattern
+            .replace_all(text, |caps: &regex::Captures| match caps.get(1) {
+                Some(word) => template.replace("{}", word.as_str()),
+                None => caps[0].to_string(),
+            })
+            .into_owned()
+    }
+}
+
+pub struct MergeSummary {
+    /// Cues written.
+    pub total: usize,
+    /// Start of the first and end of the last cue written (ms).
+    pub first_start: i64,
+    pub last_end: i64,
+    /// Each input's path and how many written cues hold a part of it.
+    pub per_source: Vec<(String, usize)>,
+    /// Output index and characters per second of cues over the reading speed limit.
+    pub fast_cues: Vec<(usize, f64)>,
+}
+
+impl fmt::Display for MergeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} written, spanning {} --> {}",
+            self.total,
+            if self.total == 1 { "cue" } else { "cues" },
+            format_timestamp(self.first_start),
+            format_timestamp(self.last_end)
+        )?;
+        let sources: Vec<String> = self
+            .per_source
+            .iter()
+            .map(|(path, count)| format!("{}: {}", path, count))
+            .collect();
+        write!(f, "\n  {}", sources.join(", "))
+    }
+}
+
+fn format_ass_timestamp(ms: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000 / 10
+    )
+}
+
+/// Formats a WebVTT timestamp, `HH:MM:SS.mmm` or, without `hours`,
+/// `MM:SS.mmm`, which only holds times below an hour.
+fn format_vtt_timestamp(ms: i64, hours: bool) -> String {
+    let short = format!(
+        "{:02}:{:02}.{:03}",
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    );
+    if hours {
+        format!("{:02}:{}", ms / 3_600_000, short)
+    } else {
+        short
+    }
+}
+
+static ASS_ALIGNMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{[^}]*\\an([1-9])").unwrap());
+
+/// Translates the first ASS `\an` alignment tag in `text` into WebVTT cue
+/// settings. Bottom-center is the default in both formats, so it yields an
+/// empty string.
+fn vtt_settings(text: &str) -> String {
+    let Some(caps) = ASS_ALIGNMENT.captures(text) else {
+        return String::new();
+    };
+    let alignment: u32 = caps[1].parse().unwrap();
+    let mut settings = Vec::new();
+    match alignment {
+        7..=9 => settings.push("line:0"),
+        4..=6 => settings.push("line:50%"),
+        _ => {}
+    }
+    match alignment % 3 {
+        1 => settings.push("align:start"),
+        0 => settings.push("align:end"),
+        _ => {}
+    }
+    settings.join(" ")
+}
+
+static OVERRIDE_BLOCK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{[^}]*\}").unwrap());
+
+/// Drops ASS override blocks and turns ASS line breaks into real ones,
+/// since WebVTT players would otherwise show them verbatim.
+fn vtt_text(text: &str) -> String {
+    OVERRIDE_BLOCK
+        .replace_all(text, "")
+        .replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", " ")
+}
+
+/// The HTML tag an ASS override tag turns into in SRT or WebVTT, if any:
+/// `\i`, `\b`, `\u` and `\s` open or close their HTML namesake and a
+/// primary `\c` color opens or, without a value, closes a `<font>`. `open`
+/// tracks what is open so far, which `\r` closes.
+fn html_for_ass_tag(tag: &str, open: &mut Vec<&'static str>) -> String {
+    let tag = tag.trim_start_matches('\\');
+    let (name, value): (&'static str, &str) = match tag.split_at(tag.len().min(1)) {
+        ("1", rest) if rest.starts_with('c') => ("font", &rest[1..]),
+        ("c", rest) if rest.is_empty() || rest.starts_with('&') => ("font", rest),
+        ("i", rest) => ("i", rest),
+        ("b", rest) => ("b", rest),
+        ("u", rest) => ("u", rest),
+        ("s", rest) => ("s", rest),
+        ("r", "") => {
+            let closed: String = open
+                .drain(..)
+                .rev()
+                .map(|name| format!("</{}>", name))
+                .collect();
+            return closed;
+        }
+        _ => return String::new(),
+    };
+    if name == "font" {
+        let hex = value
+            .trim_start_matches(['&', 'H', 'h'])
+            .trim_end_matches('&');
+        return match u32::from_str_radix(hex, 16) {
+            Ok(bgr) => {
+                open.push("font");
+                let (b, g, r) = ((bgr >> 16) & 0xff, (bgr >> 8) & 0xff, bgr & 0xff);
+                format!("<font color=\"#{:02x}{:02x}{:02x}\">", r, g, b)
+            }
+            Err(_) if hex.is_empty() => close_html(open, "font"),
+            Err(_) => String::new(),
+        };
+    }
+    // `\b` also takes a font weight; anything but 0 counts as bold.
+    match value.parse::<u32>() {
+        Ok(0) => close_html(open, name),
+        Ok(_) => {
+            open.push(name);
+            format!("<{}>", name)
+        }
+        Err(_) => String::new(),
+    }
+}
+
+/// Closes the innermost open `name` tag, or nothing if none is open.
+fn close_html(open: &mut Vec<&'static str>, name: &str) -> String {
+    match open.iter().rposition(|open| *open == name) {
+        Some(i) => {
+            open.remove(i);
+            format!("</{}>", name)
+        }
+        None => String::new(),
+    }
+}
+
+static OVERRIDE_BLOCK_TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([^}]*)\}").unwrap());
+
+/// Rewrites the ASS override tags of `text`, from an ASS input, for SRT or
+/// WebVTT output: styling tags with an HTML equivalent become HTML tags,
+/// positional ones stay in an override block, as many SRT readers honor
+/// `{\an8}`, and the rest are dropped. Tags left open are closed by
+/// [`stack_parts`].
+fn ass_tags_to_html(text: &str) -> String {
+    let mut open = Vec::new();
+    OVERRIDE_BLOCK_TAGS
+        .replace_all(text, |caps: &regex::Captures| {
+            let mut kept = String::new();
+            let mut html = String::new();
+            for tag in split_tags(&caps[1]) {
+                if !tag.starts_with('\\') {
+                    continue;
+                }
+                match TagKind::of(tag) {
+                    TagKind::Positional => kept.push_str(tag),
+                    _ => html.push_str(&html_for_ass_tag(tag, &mut open)),
+                }
+            }
+            if kept.is_empty() {
+                html
+            } else {
+                format!("{{{}}}{}", kept, html)
+            }
+        })
+        .into_owned()
+}
+
+static HTML_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)<(/?)([a-z]+)\b([^>]*)>|<[0-9][^>]*>"#).unwrap());
+static HTML_COLOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\bcolor\s*=\s*"?#([0-9a-f]{6})\b"#).unwrap());
+
+/// Rewrites the HTML tags of `text`, from an SRT or WebVTT input, for ASS
+/// output: `<i>`, `<b>`, `<u>` and `<s>` become their override tags and a
+/// `<font color>` a primary color. Other tags, such as WebVTT voices and
+/// classes, are dropped, since ASS would show them as text.
+fn html_tags_to_ass(text: &str) -> String {
+    HTML_TAG
+        .replace_all(text, |caps: &regex::Captures| {
+            let Some(name) = caps.get(2) else {
+                return String::new();
+            };
+            let closing = !caps[1].is_empty();
+            match name.as_str().to_ascii_lowercase().as_str() {
+                name @ ("i" | "b" | "u" | "s") => {
+                    format!("{{\\{}{}}}", name, if closing { 0 } else { 1 })
+                }
+                "font" if closing => "{\\c}".to_string(),
+                "font" => match HTML_COLOR.captures(&caps[3]) {
+                    Some(color) => {
+                        let rgb = color[1].to_ascii_uppercase();
+                        format!("{{\\c&H{}{}{}&}}", &rgb[4..6], &rgb[2..4], &rgb[0..2])
+                    }
+                    None => String::new(),
+                },
+                _ => String::new(),
+            }
+        })
+        .into_owned()
+}
+
+static FONT_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)</?font\b[^>]*>").unwrap());
+
+/// Drops `<font>` tags, which WebVTT does not have.
+fn strip_font_tags(text: &str) -> String {
+    FONT_TAG.replace_all(text, "").into_owned()
+}
+
+/// Undoes UTF-8 text that was decoded as Windows-1252 or Latin-1, as in
+/// `Ã©` for `é`. Only text that encodes back to Windows-1252 without loss
+/// and whose bytes then form valid UTF-8 is touched, which text meant as
+/// Latin-1, with its lone accented bytes, never does; `None` means the
+/// text was left alone.
+fn fix_mojibake(text: &str) -> Option<String> {
+    if text.is_ascii() {
+        return None;
+    }
+    let (bytes, _, unmappable) = encoding_rs::WINDOWS_1252.encode(text);
+    if unmappable {
+        return None;
+    }
+    String::from_utf8(bytes.into_owned()).ok()
+}
+
+static HTML_ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]+);").unwrap());
+
+/// Decodes numeric (`&#233;`, `&#xE9;`) and common named HTML entities.
+/// Unknown names and invalid code points are left as written.
+fn decode_entities(text: &str) -> String {
+    HTML_ENTITY
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let decoded = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => {
+                    let code = match name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => name.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                    };
+                    code.and_then(char::from_u32)
+                }
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+fn format_coord(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+static TAG_OR_BREAK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{[^}]*\}|<[^>]*>|\\[Nn]|\n").unwrap());
+
+/// The text a viewer actually reads, without ASS override blocks,
+/// HTML-style tags and line breaks.
+fn visible_text(text: &str) -> String {
+    TAG_OR_BREAK.replace_all(text, "").into_owned()
+}
+
+/// Whether `c` is written without spaces between words: Han, kana, CJK
+/// punctuation and full-width forms. Hangul is spaced, so it is not.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff00}'..='\u{ffef}'
+        | '\u{20000}'..='\u{2fa1f}')
+}
+
+static LINE_BREAK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\N|\n").unwrap());
+static DIALOGUE_DASH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[-‐‑–—]").unwrap());
+
+/// `--join-cjk-lines`: joins the `\n` and ASS `\N` separated lines of
+/// `text` into one, with no space where a CJK line meets another and a
+/// space anywhere else. Lines opening with a dialogue dash are another
+/// speaker and stay on their own.
+fn join_cjk_lines(text: &str) -> String {
+    let mut joined = String::new();
+    let mut last = 0;
+    let mut separator = "";
+    let breaks = LINE_BREAK
+        .find_iter(text)
+        .map(|found| (found.start(), found.end()))
+        .chain([(text.len(), text.len())]);
+    for (start, end) in breaks {
+        let line = text[last..start].trim();
+        let next_separator = &text[start..end];
+        last = end;
+        if line.is_empty() {
+            continue;
+        }
+        let visible = visible_text(line);
+        let visible = visible.trim();
+        if !joined.is_empty() {
+            let before = visible_text(&joined).trim_end().chars().last();
+            if DIALOGUE_DASH.is_match(visible) {
+                joined.push_str(separator);
+            } else if !(before.is_some_and(is_cjk) && visible.starts_with(is_cjk)) {
+                joined.push(' ');
+            }
+        }
+        joined.push_str(line);
+        separator = next_separator;
+    }
+    joined
+}
+
+/// Counts the characters of [`visible_text`].
+fn visible_len(text: &str) -> usize {
+    visible_text(text).chars().count()
+}
+
+/// What an ASS override tag does, which decides whether it survives
+/// conversion to SRT.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TagKind {
+    /// Places or clips the line: `\pos`, `\an`, `\org`, `\clip`, ...
+    Positional,
+    /// Changes the line over time: `\t`, `\fad`, `\move` and karaoke.
+    Animation,
+    /// Everything else, such as colors, fonts and `\i`.
+    Formatting,
+}
+
+impl TagKind {
+    /// Classifies one tag, written with its leading backslash.
+    fn of(tag: &str) -> TagKind {
+        let name: String = tag
+            .trim_start_matches('\\')
+            .chars()
+            .take_while(char::is_ascii_alphabetic)
+            .collect();
+        match name.as_str() {
+            "t" | "fad" | "fade" | "move" | "k" | "K" | "kf" | "ko" => TagKind::Animation,
+            "pos" | "an" | "a" | "org" | "clip" | "iclip" => TagKind::Positional,
+            _ => TagKind::Formatting,
+        }
+    }
+}
+
+/// Splits the inside of an override block into its tags. A `\` inside
+/// parentheses, as in `\t(\fs40)`, belongs to the enclosing tag; any text
+/// before the first tag comes first.
+fn split_tags(block: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    for (i, c) in block.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '\\' if depth == 0 && i > start => {
+                tags.push(&block[start..i]);
+                start = i;
+            }
+            _ => {}
+        }
+    }
+    if start < block.len() {
+        tags.push(&block[start..]);
+    }
+    tags
+}
+
+/// Drops the animation tags SRT has no use for, keeping where a `\move`
+/// starts as a `\pos`. Override blocks left empty disappear.
+fn static_tags(text: &str) -> String {
+    OVERRIDE_BLOCK_TAGS
+        .replace_all(text, |caps: &regex::Captures| {
+            let kept: String = split_tags(&caps[1])
+                .into_iter()
+                .filter_map(|tag| {
+                    if !tag.starts_with('\\') || TagKind::of(tag) != TagKind::Animation {
+                        return Some(tag.to_string());
+                    }
+                    let args = tag.strip_prefix("\\move(")?.trim_end_matches(')');
+                    let start: Vec<&str> = args.split(',').take(2).map(str::trim).collect();
+                    (start.len() == 2).then(|| format!("\\pos({})", start.join(",")))
+                })
+                .collect();
+            if kept.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", kept)
+            }
+        })
+        .into_owned()
+}
+
+static POSITION_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\\(pos|org|move)\(([^)]*)\)").unwrap());
+
+/// Rescales the coordinates of `\pos`, `\org` and `\move` tags by the
+/// given horizontal and vertical factors.
+fn scale_positions(text: &str, scale_x: f64, scale_y: f64) -> String {
+    POSITION_TAG
+        .replace_all(text, |caps: &regex::Captures| {
+            let args: Vec<String> = caps[2]
+                .split(',')
+                .enumerate()
+                .map(|(i, arg)| match arg.trim().parse::<f64>() {
+                    // \move carries two trailing times that must not be scaled
+                    Ok(value) if i < 4 => {
+                        format_coord(value * if i % 2 == 0 { scale_x } else { scale_y })
+                    }
+                    _ => arg.trim().to_string(),
+                })
+                .collect();
+            format!("\\{}({})", &caps[1], args.join(","))
+        })
+        .into_owned()
+}
+
+/// Configures a [`SubtitleMerger`] with chainable setters mirroring the CLI
+/// flags. Unless set, the merger writes sequential entries, keeps exact-start
+/// bundling for the vertical strategy, rescales ASS positions to the first
+/// PlayRes, ignores ASS comments, ends SRT output with a blank line and
+/// refuses to overwrite its inputs.
+pub struct SubtitleMergerBuilder {
+    merger: SubtitleMerger,
+}
+
+impl SubtitleMergerBuilder {
+    pub fn output(mut self, path: impl AsRef<Path>) -> Self {
+        self.merger.output_path = path.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merger.strategy = strategy;
+        self
+    }
+
+    pub fn bundle_window(mut self, window_ms: i64) -> Self {
+        self.merger.bundle_window = window_ms;
+        self
+    }
+
+    pub fn priority(mut self, sources: Vec<usize>) -> Self {
+        self.merger.priority = sources;
+        self
+    }
+
+    pub fn rescale_positions(mut self, rescale: bool) -> Self {
+        self.merger.rescale_positions = rescale;
+        self
+    }
+
+    pub fn include_comments(mut self, include: bool) -> Self {
+        self.merger.include_comments = include;
+        self
+    }
+
+    pub fn ignore_format_errors(mut self, ignore: bool) -> Self {
+        self.merger.ignore_format_errors = ignore;
+        self
+    }
+
+    pub fn reading_speed_limit(mut self, cps: Option<f64>) -> Self {
+        self.merger.reading_speed_limit = cps;
+        self
+    }
+
+    pub fn force(mut self, force: bool) -> Self {
+        self.merger.force = force;
+        self
+    }
+
+    pub fn filter(mut self, pattern: Regex) -> Self {
+        self.merger.filters.push(pattern);
+        self
+    }
+
+    pub fn replacement(mut self, pattern: Regex, replacement: &str) -> Self {
+        self.merger
+            .replacements
+            .push((pattern, replacement.to_string()));
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Option<std::time::Duration>) -> Self {
+        self.merger.timeout = timeout;
+        self
+    }
+
+    pub fn final_newline(mut self, final_newline: bool) -> Self {
+        self.merger.final_newline = final_newline;
+        self
+    }
+
+    pub fn layer_per_source(mut self, layer_per_source: bool) -> Self {
+        self.merger.layer_per_source = layer_per_source;
+        self
+    }
+
+    pub fn concat(mut self, concat: bool, gaps: Vec<i64>) -> Self {
+        self.merger.concat = concat;
+        self.merger.concat_gaps = gaps;
+        self
+    }
+
+    pub fn max_output_bytes(mut self, limit: Option<u64>) -> Self {
+        self.merger.max_output_bytes = limit;
+        self
+    }
+
+    pub fn ass_rounding(mut self, rounding: AssRounding) -> Self {
+        self.merger.ass_rounding = rounding;
+        self
+    }
+
+    pub fn renumber(mut self, renumber: bool) -> Self {
+        self.merger.renumber = renumber;
+        self
+    }
+
+    pub fn attribution(mut self, path: Option<PathBuf>) -> Self {
+        self.merger.attribution_path = path;
+        self
+    }
+
+    pub fn keep_styling_map(mut self, keep: bool) -> Self {
+        self.merger.keep_styling_map = keep;
+        self
+    }
+
+    pub fn dedup(mut self, keep: Option<KeepVariant>) -> Self {
+        self.merger.dedup = keep;
+        self
+    }
+
+    pub fn intersect(mut self, intersect: bool) -> Self {
+        self.merger.intersect = intersect;
+        self
+    }
+
+    pub fn lang_detect(mut self, detect: bool) -> Self {
+        self.merger.lang_detect = detect;
+        self
+    }
+
+    pub fn decode_entities(mut self, decode: bool) -> Self {
+        self.merger.decode_entities = decode;
+        self
+    }
+
+    pub fn dedup_inputs(mut self, dedup: bool) -> Self {
+        self.merger.dedup_inputs = dedup;
+        self
+    }
+
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.merger.fail_fast = fail_fast;
+        self
+    }
+
+    pub fn max_lines(mut self, max_lines: Option<usize>) -> Self {
+        self.merger.max_lines = max_lines;
+        self
+    }
+
+    /// Positions copied from `script` are in its resolution, so its
+    /// PlayRes becomes the merged script's.
+    pub fn style_from(mut self, script: Option<Subtitle>) -> Self {
+        if let Some(play_res) = script.as_ref().and_then(|script| script.play_res) {
+            self.merger.play_res = Some(play_res);
+        }
+        self.merger.style_from = script;
+        self
+    }
+
+    pub fn fill_gaps(mut self, fill: bool, max_ms: Option<i64>) -> Self {
+        self.merger.fill_gaps = fill;
+        self.merger.fill_max = max_ms;
+        self
+    }
+
+    pub fn silence_markers(mut self, gap_ms: Option<i64>, text: &str) -> Self {
+        self.merger.silence_gap = gap_ms;
+        self.merger.silence_text = text.to_string();
+        self
+    }
+
+    pub fn trim_whitespace_lines(mut self, trim: bool) -> Self {
+        self.merger.trim_whitespace_lines = trim;
+        self
+    }
+
+    pub fn positions(mut self, alignments: Vec<u8>) -> Self {
+        self.merger.positions = alignments;
+        self
+    }
+
+    pub fn chunking(mut self, max_cues: Option<usize>, max_duration_ms: Option<i64>) -> Self {
+        self.merger.chunk_cues = max_cues;
+        self.merger.chunk_duration = max_duration_ms;
+        self
+    }
+
+    pub fn rebase(mut self, lead_in_ms: Option<i64>) -> Self {
+        self.merger.rebase = lead_in_ms;
+        self
+    }
+
+    pub fn fix_swapped(mut self, fix: bool) -> Self {
+        self.merger.fix_swapped = fix;
+        self
+    }
+
+    pub fn one_line(mut self, separator: Option<&str>) -> Self {
+        self.merger.one_line = separator.map(String::from);
+        self
+    }
+
+    pub fn snap_to(mut self, mut points_ms: Vec<i64>, tolerance_ms: i64) -> Self {
+        points_ms.sort_unstable();
+        self.merger.snap_points = points_ms;
+        self.merger.snap_tolerance = tolerance_ms;
+        self
+    }
+
+    pub fn ms_separator(mut self, separator: Option<char>) -> Self {
+        self.merger.ms_separator = separator;
+        self
+    }
+
+    pub fn vtt_omit_hours(mut self, omit: bool) -> Self {
+        self.merger.vtt_omit_hours = omit;
+        self
+    }
+
+    pub fn strict_timestamps(mut self, tolerance_ms: Option<i64>) -> Self {
+        self.merger.strict_timestamps = tolerance_ms;
+        self
+    }
+
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.merger.quiet = quiet;
+        self
+    }
+
+    pub fn collapse_consecutive(mut self, gap_ms: Option<i64>) -> Self {
+        self.merger.collapse_gap = gap_ms;
+        self
+    }
+
+    pub fn keep_unknown_sections(mut self, keep: bool) -> Self {
+        self.merger.keep_unknown_sections = keep;
+        self
+    }
+
+    pub fn vocab(mut self, vocab: Option<Vocab>) -> Self {
+        self.merger.vocab = vocab;
+        self
+    }
+
+    pub fn media_duration(mut self, duration_ms: Option<i64>, clamp: bool) -> Self {
+        self.merger.media_duration = duration_ms;
+        self.merger.clamp_to_media = clamp;
+        self
+    }
+
+    pub fn diff_friendly(mut self, diff_friendly: bool) -> Self {
+        self.merger.diff_friendly = diff_friendly;
+        self
+    }
+
+    pub fn min_chars(mut self, min_chars: Option<usize>) -> Self {
+        self.merger.min_chars = min_chars;
+        self
+    }
+
+    pub fn output_format(mut self, format: Option<String>) -> Self {
+        self.merger.output_format = format;
+        self
+    }
+
+    pub fn index_width(mut self, width: usize) -> Self {
+        self.merger.index_width = width;
+        self
+    }
+
+    pub fn fix_mojibake(mut self, fix: bool) -> Self {
+        self.merger.fix_mojibake = fix;
+        self
+    }
+
+    pub fn speaker_style(mut self, style: Option<SpeakerStyle>) -> Self {
+        self.merger.speaker_style = style;
+        self
+    }
+
+    pub fn join_cjk_lines(mut self, join: bool) -> Self {
+        self.merger.join_cjk_lines = join;
+        self
+    }
+
+    pub fn per_block_encoding(mut self, per_block: bool) -> Self {
+        self.merger.per_block_encoding = per_block;
+        self
+    }
+
+    pub fn hard_space(mut self, hard_space: char, collapse_nbsp: bool) -> Self {
+        self.merger.hard_space = hard_space;
+        self.merger.collapse_nbsp = collapse_nbsp;
+        self
+    }
+
+    pub fn sample_every(mut self, n: Option<usize>) -> Self {
+        self.merger.sample_every = n;
+        self
+    }
+
+    pub fn build(self) -> SubtitleMerger {
+        self.merger
+    }
+}
+
+impl SubtitleMerger {
+    /// Starts a merger with every option at its default, as listed on
+    /// [`SubtitleMergerBuilder`]: sequential entries, SRT output and no
+    /// transforms.
+    pub fn builder() -> SubtitleMergerBuilder {
+        SubtitleMergerBuilder {
+            merger: SubtitleMerger::new(""),
+        }
+    }
+
+    fn new(output_path: impl AsRef<Path>) -> Self {
+        SubtitleMerger {
+            subtitles: Vec::new(),
+            output_path: output_path.as_ref().to_path_buf(),
+            play_res: None,
+            rescale_positions: true,
+            include_comments: false,
+            strategy: MergeStrategy::Sequential,
+            bundle_window: 0,
+            priority: Vec::new(),
+            ignore_format_errors: false,
+            reading_speed_limit: None,
+            force: false,
+            filters: Vec::new(),
+            replacements: Vec::new(),
+            timeout: None,
+            final_newline: true,
+            layer_per_source: false,
+            positions: Vec::new(),
+            concat: false,
+            concat_gaps: Vec::new(),
+            attribution_path: None,
+            keep_styling_map: false,
+            intersect: false,
+            lang_detect: false,
+            decode_entities: false,
+            dedup_inputs: false,
+            fail_fast: false,
+            max_lines: None,
+            silence_gap: None,
+            silence_text: "[silence]".to_string(),
+            trim_whitespace_lines: false,
+            chunk_cues: None,
+            chunk_duration: None,
+            rebase: None,
+            snap_points: Vec::new(),
+            snap_tolerance: 0,
+            one_line: None,
+            fix_swapped: false,
+            dedup: None,
+            renumber: true,
+            ass_rounding: AssRounding::Nearest,
+            header_comment: Vec::new(),
+            max_output_bytes: None,
+            ms_separator: None,
+            vtt_omit_hours: false,
+            strict_timestamps: None,
+            quiet: false,
+            collapse_gap: None,
+            keep_unknown_sections: false,
+            vocab: None,
+            media_duration: None,
+            clamp_to_media: false,
+            diff_friendly: false,
+            output_format: None,
+            index_width: 0,
+            fix_mojibake: false,
+            per_block_encoding: false,
+            hard_space: ' ',
+            collapse_nbsp: false,
+            sample_every: None,
+            speaker_style: None,
+            min_chars: None,
+            join_cjk_lines: false,
+            fill_gaps: false,
+            fill_max: None,
+            style_from: None,
+        }
+    }
+
+    /// Loads one input, applies `options` and the merger's transforms, and
+    /// keeps it for [`SubtitleMerger::merge`].
+    pub fn add(
+        &mut self,
+        subtitle_address: impl AsRef<Path>,
+        options: &InputOptions,
+    ) -> Result<(), MergeError> {
+        let subtitle = self.load(subtitle_address.as_ref(), options)?;
+        self.push(subtitle);
+        Ok(())
+    }
+
+    /// Adds several inputs, parsing them concurrently when the `rayon`
+    /// feature is enabled. Subtitles are stored in input order either way.
+    pub fn add_all<P: AsRef<Path> + Sync>(
+        &mut self,
+        inputs: &[(P, InputOptions)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inputs = if self.dedup_inputs {
+            self.unique_inputs(inputs)
+        } else {
+            inputs.iter().collect()
+        };
+
+        // Failing fast needs the inputs in order, so it stays sequential.
+        #[cfg(feature = "rayon")]
+        if inputs.len() > 1 && !self.fail_fast {
+            use rayon::prelude::*;
+
+            let loaded: Vec<Result<Subtitle, MergeError>> = inputs
+                .par_iter()
+                .map(|(path, options)| self.load(path.as_ref(), options))
+                .collect();
+            let mut failures = Vec::new();
+            for ((path, _), subtitle) in inputs.iter().zip(loaded) {
+                match subtitle {
+                    Ok(subtitle) => self.push(subtitle),
+                    Err(e) => failures.push((path.as_ref(), e)),
+                }
+            }
+            return Ok(Self::report_failures(&failures, inputs.len())?);
+        }
+
+        let mut failures = Vec::new();
+        for (path, options) in &inputs {
+            match self.add(path, options) {
+                Ok(()) => {}
+                Err(e) if self.fail_fast => return Err(e.into()),
+                Err(e) => failures.push((path.as_ref(), e)),
+            }
+        }
+        Ok(Self::report_failures(&failures, inputs.len())?)
+    }
+
+    /// Lists every input that failed to load, then fails like the first one
+    /// if there was any.
+    fn report_failures(failures: &[(&Path, MergeError)], total: usize) -> Result<(), MergeError> {
+        if failures.is_empty() {
+            return Ok(());
+        }
+        let width = failures
+            .iter()
+            .map(|(path, _)| path.display().to_string().chars().count())
+            .max()
+            .unwrap_or(0);
+        eprintln!("{} of {} inputs failed:", failures.len(), total);
+        for (path, error) in failures {
+            eprintln!(
+                "  {:<width$}  {}",
+                path.display().to_string(),
+                error,
+                width = width
+            );
+        }
+        Err(failures[0].1.with_message(format!(
+            "{} of {} inputs failed to load",
+            failures.len(),
+            total
+        )))
+    }
+
+    /// Drops inputs whose file content is byte-identical to an earlier
+    /// input's, reporting each one skipped. Unreadable inputs are kept so loading them
+    /// reports the error.
+    fn unique_inputs<'a, P: AsRef<Path>>(
+        &self,
+        inputs: &'a [(P, InputOptions)],
+    ) -> Vec<&'a (P, InputOptions)> {
+        use std::hash::{Hash, Hasher};
+
+        // The hash only narrows the search; equal hashes are confirmed
+        // byte for byte, so a collision never drops a distinct input.
+        let mut seen: Vec<(u64, Vec<u8>, &Path)> = Vec::new();
+        let mut unique = Vec::new();
+        for input in inputs {
+            let path = input.0.as_ref();
+            if let Ok(bytes) = std::fs::read(path) {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                bytes.hash(&mut hasher);
+                let hash = hasher.finish();
+                let duplicate = seen
+                    .iter()
+                    .find(|(seen, content, _)| *seen == hash && *content == bytes);
+                if let Some((_, _, first)) = duplicate {
+                    self.note(format!(
+                        "'{}' is identical to '{}'; using it once",
+                        path.display(),
+                        first.display()
+                    ));
+                    continue;
+                }
+                seen.push((hash, bytes, path));
+            }
+            unique.push(input);
+        }
+        unique
+    }
+
+    /// Reads and parses one input; nothing here depends on other inputs.
+    fn load(&self, path: &Path, options: &InputOptions) -> Result<Subtitle, MergeError> {
+        let subtitle_address = path.to_string_lossy();
+        let subtitle_address = subtitle_address.as_ref();
+        if !(options.scale.is_finite() && options.scale > 0.0) {
+            return Err(MergeError::Validation(format!(
+                "'{}': scale {} must be a finite number greater than 0",
+                subtitle_address, options.scale
+            )));
+        }
+        let (content, encoding) = self.read_input(path)?;
+
+        let format = match &options.format {
+            Some(format) => format.as_str(),
+            None => detect_format(&content),
+        };
+        let mut subtitle = parse_str(&content, format);
+
+        if self.ignore_format_errors && !matches!(&subtitle, Ok(sub) if !sub.dialogs.is_empty()) {
+            for fallback in ["srt", "vtt", "ass"].into_iter().filter(|&f| f != format) {
+                if let Ok(parsed) = parse_str(&content, fallback) {
+                    if !parsed.dialogs.is_empty() {
+                        self.note(format!(
+                            "'{}' parsed with the {} parser after the {} parser failed",
+                            subtitle_address, fallback, format
+                        ));
+                        subtitle = Ok(parsed);
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut subtitle = subtitle
+            .map_err(|e| e.with_message(format!("Cannot parse '{}': {}", subtitle_address, e)))?;
+        subtitle.path = subtitle_address.to_string();
+        subtitle.encoding = encoding;
+        if let Some(preamble) = &subtitle.preamble {
+            self.note(format!(
+                "Warning: '{}': skipped {} lines before the first cue",
+                subtitle_address,
+                preamble.lines().count()
+            ));
+        }
+        if !self.include_comments {
+            subtitle.comments.clear();
+        }
+        if self.fix_mojibake {
+            let mut repaired = 0;
+            for dialog in subtitle
+                .dialogs
+                .values_mut()
+                .chain(subtitle.comments.values_mut())
+            {
+                if let Some(text) = fix_mojibake(&dialog.text) {
+                    dialog.text = text;
+                    repaired += 1;
+                }
+            }
+            if repaired > 0 {
+                self.note(format!(
+                    "'{}': repaired mojibake in {} cues",
+                    subtitle_address, repaired
+                ));
+            }
+        }
+        if self.decode_entities {
+            for dialog in subtitle
+                .dialogs
+                .values_mut()
+                .chain(subtitle.comments.values_mut())
+            {
+                dialog.text = decode_entities(&dialog.text);
+            }
+        }
+        if let Some(style) = &self.speaker_style {
+            for dialog in subtitle
+                .dialogs
+                .values_mut()
+                .chain(subtitle.comments.values_mut())
+            {
+                dialog.text = style.normalize(&dialog.text);
+            }
+        }
+        if self.join_cjk_lines {
+            for dialog in subtitle
+                .dialogs
+                .values_mut()
+                .chain(subtitle.comments.values_mut())
+            {
+                dialog.text = join_cjk_lines(&dialog.text);
+            }
+        }
+        if options.romanize {
+            for dialog in subtitle.dialogs.values_mut() {
+                let romanized = Self::romanize(&dialog.text)?;
+                if romanized != dialog.text {
+                    dialog.text = format!("{}\n{}", dialog.text, romanized);
+                }
+            }
+        }
+        if let Some(tolerance) = self.strict_timestamps {
+            Self::check_strict(&subtitle, tolerance)?;
+        }
+        self.check_durations(&mut subtitle);
+        self.apply_filters(&mut subtitle);
+        if let Some(min_chars) = self.min_chars {
+            self.drop_short_cues(&mut subtitle, min_chars);
+        }
+        if let Some(gap) = self.collapse_gap {
+            self.collapse_consecutive(&mut subtitle, gap);
+        }
+        if self.lang_detect {
+            subtitle.language = Self::detect_language(&subtitle)?;
+        }
+        if options.scale != 1.0 {
+            Self::retime(&mut subtitle, |ms| {
+                (ms as f64 * options.scale).round() as i64
+            });
+        }
+        if options.offset != 0 {
+            Self::retime(&mut subtitle, |ms| ms + options.offset);
+        }
+        if let Some(target) = options.first_cue_at {
+            let earliest = subtitle
+                .dialogs
+                .keys()
+                .chain(subtitle.comments.keys())
+                .min()
+                .copied();
+            if let Some(earliest) = earliest {
+                Self::retime(&mut subtitle, |ms| ms + target - earliest);
+            }
+        }
+        if !options.cuts.is_empty() {
+            self.cut_ranges(&mut subtitle, &options.cuts);
+        }
+        if let Some(script) = self
+            .style_from
+            .as_ref()
+            .filter(|_| subtitle.format != "ass")
+        {
+            self.apply_styles_from(&mut subtitle, script);
+        }
+        Ok(subtitle)
+    }
+
+    /// `--style-from`: gives each cue the style of the `script` event
+    /// overlapping it the longest, if that covers at least half the cue,
+    /// along with the tags that place the event, from the override block
+    /// opening its text: `\an`, `\pos`, `\move`, `\fad` and the like.
+    /// Formatting tags in that block may only cover some words of the
+    /// event, so they are not copied.
+    fn apply_styles_from(&self, subtitle: &mut Subtitle, script: &Subtitle) {
+        static LEADING_OVERRIDE_BLOCK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^\{([^}]*)\}").unwrap());
+        let mut events: Vec<(&i64, &Dialog)> = script.dialogs.iter().collect();
+        events.sort_unstable_by_key(|&(&start, event)| (start, event.end));
+        let mut styled = 0;
+        for (&start, dialog) in subtitle.dialogs.iter_mut() {
+            // Reversed so that of equally long overlaps the earliest wins.
+            let best = events
+                .iter()
+                .rev()
+                .map(|&(&event_start, event)| {
+                    (event, dialog.end.min(event.end) - start.max(event_start))
+                })
+                .max_by_key(|&(_, overlap)| overlap);
+            let Some((event, overlap)) = best else {
+                continue;
+            };
+            if overlap <= 0 || overlap * 2 < dialog.end - start {
+                continue;
+            }
+            dialog.style = event.style.clone();
+            let placement: String = LEADING_OVERRIDE_BLOCK
+                .captures(&event.text)
+                .map(|block| {
+                    split_tags(&block[1])
+                        .into_iter()
+                        .filter(|tag| match TagKind::of(tag) {
+                            TagKind::Positional => true,
+                            TagKind::Animation => ["\\move(", "\\fad(", "\\fade("]
+                                .iter()
+                                .any(|name| tag.starts_with(name)),
+                            TagKind::Formatting => false,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            if !placement.is_empty() {
+                dialog.text = format!("{{{}}}{}", placement, dialog.text);
+            }
+            styled += 1;
+        }
+        self.note(format!(
+            "'{}': took styles from '{}' for {} of {} cues",
+            subtitle.path,
+            script.path,
+            styled,
+            subtitle.dialogs.len()
+        ));
+    }
+
+    /// Removes each `(start, end)` span from the timeline: cues entirely
+    /// inside it are dropped, times inside it move to its start and times
+    /// after it move earlier by its length.
+    fn cut_ranges(&self, subtitle: &mut Subtitle, cuts: &[(i64, i64)]) {
+        let mut cuts = cuts.to_vec();
+        // From the latest cut back, so each one's times are still as given.
+        cuts.sort_unstable_by_key(|&(start, _)| std::cmp::Reverse(start));
+        let mut dropped = 0;
+        for (cut_start, cut_end) in cuts {
+            for dialogs in [&mut subtitle.dialogs, &mut subtitle.comments] {
+                let before = dialogs.len();
+                dialogs.retain(|&start, dialog| start < cut_start || dialog.end > cut_end);
+                dropped += before - dialogs.len();
+            }
+            Self::retime(subtitle, |ms| {
+                if ms >= cut_end {
+                    ms - (cut_end - cut_start)
+                } else {
+                    ms.min(cut_start)
+                }
+            });
+        }
+        if dropped > 0 {
+            self.note(format!(
+                "'{}': dropped {} cues inside --cut-range",
+                subtitle.path, dropped
+            ));
+        }
+    }
+
+    /// What was loaded from each input kept so far, in input order.
+    pub fn sources(&self) -> Vec<SourceInfo> {
+        self.subtitles.iter().map(SourceInfo::from).collect()
+    }
+
+    /// Loads each input on its own without keeping it, for `--check`. An
+    /// input fails if it cannot be read, is not a known format, does not
+    /// parse or holds no cues.
+    pub fn check<P: AsRef<Path>>(
+        &self,
+        inputs: &[(P, InputOptions)],
+    ) -> Vec<(String, Result<SourceInfo, String>)> {
+        inputs
+            .iter()
+            .map(|(path, options)| {
+                let path = path.as_ref();
+                let result = match self.load(path, options) {
+                    Ok(subtitle) if subtitle.dialogs.is_empty() && subtitle.comments.is_empty() => {
+                        Err("no cues found".to_string())
+                    }
+                    Ok(subtitle) => Ok(SourceInfo::from(&subtitle)),
+                    Err(e) => Err(e.to_string()),
+                };
+                (path.display().to_string(), result)
+            })
+            .collect()
+    }
+
+    /// A table of each input's first cue start and last cue end as written
+    /// and after its scale and offset, for `--offset-preview`. Times the
+    /// merge clamps to zero are shown clamped.
+    pub fn offset_preview<P: AsRef<Path>>(
+        &mut self,
+        inputs: &[(P, InputOptions)],
+    ) -> Result<String, MergeError> {
+        let span = |subtitle: &Subtitle| {
+            let first = subtitle.dialogs.keys().min()?;
+            let last = subtitle.dialogs.values().map(|dialog| dialog.end).max()?;
+            Some((*first, last))
+        };
+        let mut rows = vec![["input", "first cue", "shift", "last cue", "shift"].map(String::from)];
+        for (path, options) in inputs {
+            let path = path.as_ref();
+            let transformed = self.load(path, options)?;
+            // The first load has printed the notes already.
+            let quiet = std::mem::replace(&mut self.quiet, true);
+            let as_written = InputOptions {
+                format: options.format.clone(),
+                ..InputOptions::default()
+            };
+            let original = self.load(path, &as_written);
+            self.quiet = quiet;
+            rows.push(match (span(&original?), span(&transformed)) {
+                (Some((first, last)), Some((new_first, new_last))) => {
+                    let (new_first, new_last) = (new_first.max(0), new_last.max(0));
+                    [
+                        path.display().to_string(),
+                        format!(
+                            "{} -> {}",
+                            format_timestamp(first),
+                            format_timestamp(new_first)
+                        ),
+                        format!("{:+}ms", new_first - first),
+                        format!(
+                            "{} -> {}",
+                            format_timestamp(last),
+                            format_timestamp(new_last)
+                        ),
+                        format!("{:+}ms", new_last - last),
+                    ]
+                }
+                _ => [
+                    path.display().to_string(),
+                    "no cues".to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ],
+            });
+        }
+        let mut widths = [0; 5];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut table = String::new();
+        for row in &rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            table.push_str(cells.join("  ").trim_end());
+            table.push('\n');
+        }
+        Ok(table)
+    }
+
+    fn push(&mut self, mut subtitle: Subtitle) {
+        if subtitle.play_res.is_some() {
+            self.reconcile_play_res(&mut subtitle);
+        }
+        if self.concat && !self.subtitles.is_empty() {
+            self.append_part(&mut subtitle);
+        }
+        self.clamp_negative(&mut subtitle);
+        self.subtitles.push(subtitle);
+    }
+
+    /// Runs after every retiming transform: cues starting before zero are
+    /// clamped to start at zero, and cues that also end by zero are dropped.
+    fn clamp_negative(&self, subtitle: &mut Subtitle) {
+        let mut clamped = 0;
+        let mut dropped = 0;
+        for dialogs in [&mut subtitle.dialogs, &mut subtitle.comments] {
+            if dialogs.keys().all(|&start| start >= 0) {
+                continue;
+            }
+            for (start, dialog) in std::mem::take(dialogs) {
+                if start >= 0 {
+                    insert_dialog(dialogs, start, dialog);
+                } else if dialog.end > 0 {
+                    clamped += 1;
+                    insert_dialog(dialogs, 0, dialog);
+                } else {
+                    dropped += 1;
+                }
+            }
+        }
+        if clamped > 0 || dropped > 0 {
+            self.note(format!(
+                "'{}': clamped {} cues to start at 00:00:00, dropped {} cues ending before it",
+                subtitle.path, clamped, dropped
+            ));
+        }
+    }
+
+    /// Moves a concatenated part so it starts where the previous parts end,
+    /// plus the gap configured for this boundary.
+    fn append_part(&self, subtitle: &mut Subtitle) {
+        let previous_end = self
+            .subtitles
+            .iter()
+            .flat_map(|sub| sub.dialogs.values().chain(sub.comments.values()))
+            .map(|dialog| dialog.end)
+            .max()
+            .unwrap_or(0);
+        let boundary = self.subtitles.len() - 1;
+        let gap = match self.concat_gaps.as_slice() {
+            [] => 0,
+            [gap] => *gap,
+            gaps => gaps.get(boundary).copied().unwrap_or(0),
+        };
+        let offset = previous_end + gap;
+        Self::retime(subtitle, |ms| ms + offset);
+    }
+
+    /// Reads and decodes an input: [`decode`], or [`decode_blocks`] with `per_block_encoding`.
+    fn read_input(&self, path: &Path) -> Result<(String, &'static str), MergeError> {
+        let url = path
+            .to_str()
+            .filter(|url| url.starts_with("http://") || url.starts_with("https://"));
+        // `pack.zip:movie.en.srt` names an entry inside an archive.
+        let entry = path.to_str().and_then(|path| {
+            let end = path.to_ascii_lowercase().find(".zip:")? + ".zip".len();
+            Some((Path::new(&path[..end]), &path[end + 1..]))
+        });
+        let bytes = match (url, entry) {
+            (Some(url), _) => self.fetch(url)?,
+            (None, Some((archive, entry))) => Self::read_zip_entry(archive, entry)?,
+            (None, None) => {
+                let mut bytes = Vec::new();
+                File::open(path)
+                    .and_then(|mut file| file.read_to_end(&mut bytes))
+                    .map_err(|e| {
+                        MergeError::Io(format!("Cannot read '{}': {}", path.display(), e))
+                    })?;
+                bytes
+            }
+        };
+
+        if !self.per_block_encoding {
+            return Ok(decode(&bytes));
+        }
+        let (content, encodings) = decode_blocks(&bytes);
+        if encodings.len() > 1 {
+            let counts: Vec<String> = encodings
+                .iter()
+                .map(|(encoding, blocks)| format!("{} in {} blocks", encoding, blocks))
+                .collect();
+            self.note(format!(
+                "Warning: '{}' mixes encodings: {}",
+                path.display(),
+                counts.join(", ")
+            ));
+        }
+        Ok((content, encodings[0].0))
+    }
+
+    #[cfg(feature = "zip")]
+    fn read_zip_entry(archive: &Path, entry: &str) -> Result<Vec<u8>, MergeError> {
+        let error = |e: &dyn fmt::Display| {
+            MergeError::Io(format!("Cannot read '{}': {}", archive.display(), e))
+        };
+        let mut zip = File::open(archive)
+            .map_err(|e| error(&e))
+            .and_then(|file| zip::ZipArchive::new(file).map_err(|e| error(&e)))?;
+        let Some(index) = zip.index_for_name(entry) else {
+            let mut names: Vec<&str> = zip.file_names().collect();
+            names.sort_unstable();
+            return Err(MergeError::Io(format!(
+                "'{}' has no entry '{}'; it holds {}",
+                archive.display(),
+                entry,
+                names.join(", ")
+            )));
+        };
+        let mut bytes = Vec::new();
+        zip.by_index(index)
+            .map_err(|e| error(&e))?
+            .read_to_end(&mut bytes)
+            .map_err(|e| error(&e))?;
+        Ok(bytes)
+    }
+
+    #[cfg(not(feature = "zip"))]
+    fn read_zip_entry(archive: &Path, entry: &str) -> Result<Vec<u8>, MergeError> {
+        Err(MergeError::Validation(format!(
+            "Cannot read '{}' from '{}': built without the 'zip' feature",
+            entry,
+            archive.display()
+        )))
+    }
+
+    #[cfg(feature = "net")]
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, MergeError> {
+        let mut agent = ureq::AgentBuilder::new();
+        if let Some(timeout) = self.timeout {
+            agent = agent.timeout(timeout);
+        }
+        let response = match agent.build().get(url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                return Err(MergeError::Io(format!(
+                    "Fetching '{}' failed: HTTP {} {}",
+                    url,
+                    code,
+                    response.status_text()
+                )))
+            }
+            Err(e) => return Err(MergeError::Io(format!("Fetching '{}' failed: {}", url, e))),
+        };
+        let mut content = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut content)
+            .map_err(|e| MergeError::Io(format!("Fetching '{}' failed: {}", url, e)))?;
+        Ok(content)
+    }
+
+    #[cfg(not(feature = "net"))]
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, MergeError> {
+        let _ = self.timeout;
+        Err(MergeError::Validation(format!(
+            "Cannot fetch '{}': built without the 'net' feature",
+            url
+        )))
+    }
+
+    /// Guesses the language from the text of the first cues, giving up when
+    /// whatlang is not confident; `{langs}` then falls back to the stem.
+    #[cfg(feature = "lang")]
+    fn detect_language(subtitle: &Subtitle) -> Result<Option<String>, MergeError> {
+        const SAMPLE_CUES: usize = 50;
+        const MIN_CONFIDENCE: f64 = 0.5;
+
+        static TAG_OR_ASS_BREAK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"\{[^}]*\}|<[^>]*>|\\[Nn]").unwrap());
+        let mut starts: Vec<&i64> = subtitle.dialogs.keys().collect();
+        starts.sort_unstable();
+        let sample: Vec<String> = starts
+            .into_iter()
+            .take(SAMPLE_CUES)
+            .map(|start| {
+                TAG_OR_ASS_BREAK
+                    .replace_all(&subtitle.dialogs[start].text, " ")
+                    .into_owned()
+            })
+            .collect();
+        Ok(whatlang::detect(&sample.join("\n"))
+            .filter(|info| info.confidence() >= MIN_CONFIDENCE)
+            .map(|info| info.lang().code().to_string()))
+    }
+
+    #[cfg(not(feature = "lang"))]
+    fn detect_language(subtitle: &Subtitle) -> Result<Option<String>, MergeError> {
+        Err(MergeError::Validation(format!(
+            "Cannot detect the language of '{}': built without the 'lang' feature",
+            subtitle.path
+        )))
+    }
+
+    /// Approximates `text` in Latin script; already Latin text is unchanged.
+    #[cfg(feature = "translit")]
+    fn romanize(text: &str) -> Result<String, MergeError> {
+        Ok(deunicode::deunicode(text))
+    }
+
+    #[cfg(not(feature = "translit"))]
+    fn romanize(_text: &str) -> Result<String, MergeError> {
+        Err(MergeError::Validation(
+            "Cannot romanize: built without the 'translit' feature".to_string(),
+        ))
+    }
+
+    /// The first input declaring a PlayRes sets the resolution of the merged
+    /// script; positional tags of later inputs are scaled to match it.
+    fn reconcile_play_res(&mut self, subtitle: &mut Subtitle) {
+        let Some((width, height)) = subtitle.play_res else {
+            return;
+        };
+        let Some((target_width, target_height)) = self.play_res else {
+            self.play_res = subtitle.play_res;
+            return;
+        };
+        if (width, height) == (target_width, target_height) {
+            return;
+        }
+
+        if !self.rescale_positions {
+            self.note(format!(
+                "Warning: '{}' uses PlayRes {}x{} but the merged script uses {}x{}; positional tags left unscaled",
+                subtitle.path, width, height, target_width, target_height
+            ));
+            return;
+        }
+
+        let scale_x = target_width as f64 / width as f64;
+        let scale_y = target_height as f64 / height as f64;
+        for dialog in subtitle
+            .dialogs
+            .values_mut()
+            .chain(subtitle.comments.values_mut())
+        {
+            dialog.text = scale_positions(&dialog.text, scale_x, scale_y);
+        }
+    }
+
+    /// Extends each cue over the cues right after it that repeat its text
+    /// and start at most `gap` ms after it ends, dropping those.
+    fn collapse_consecutive(&self, subtitle: &mut Subtitle, gap: i64) {
+        let mut starts: Vec<i64> = subtitle.dialogs.keys().copied().collect();
+        starts.sort_unstable();
+        let mut current: Option<i64> = None;
+        let mut collapsed = 0;
+        for start in starts {
+            if let Some(kept) = current {
+                let next = subtitle.dialogs[&start].clone();
+                let kept = subtitle.dialogs.get_mut(&kept).unwrap();
+                if kept.text.trim() == next.text.trim() && start - kept.end <= gap {
+                    kept.end = kept.end.max(next.end);
+                    subtitle.dialogs.remove(&start);
+                    collapsed += 1;
+                    continue;
+                }
+            }
+            current = Some(start);
+        }
+        if collapsed > 0 {
+            self.note(format!(
+                "'{}': collapsed {} repeated cues into the ones before them",
+                subtitle.path, collapsed
+            ));
+        }
+    }
+
+    /// Drops cues matching any `--filter` pattern and applies the
+    /// `--filter-replace` substitutions to the rest.
+    fn apply_filters(&self, subtitle: &mut Subtitle) {
+        if self.filters.is_empty() && self.replacements.is_empty() {
+            return;
+        }
+        let mut dropped = 0;
+        let mut replaced = 0;
+        for dialogs in [&mut subtitle.dialogs, &mut subtitle.comments] {
+            dialogs.retain(|_, dialog| {
+                if self
+                    .filters
+                    .iter()
+                    .any(|filter| filter.is_match(&dialog.text))
+                {
+                    dropped += 1;
+                    return false;
+                }
+                for (pattern, replacement) in &self.replacements {
+                    let text = pattern.replace_all(&dialog.text, replacement.as_str());
+                    if text != dialog.text {
+                        dialog.text = text.into_owned();
+                        replaced += 1;
+                    }
+                }
+                !dialog.text.trim().is_empty()
+            });
+        }
+        if dropped > 0 || replaced > 0 {
+            self.note(format!(
+                "'{}': filtered {} cues, {} replacements",
+                subtitle.path, dropped, replaced
+            ));
+        }
+    }
+
+    /// Drops the cues whose text, without tags and surrounding spaces, is
+    /// shorter than `min_chars` characters, such as stray symbols left by
+    /// automatic captions.
+    fn drop_short_cues(&self, subtitle: &mut Subtitle, min_chars: usize) {
+        let before = subtitle.dialogs.len();
+        subtitle
+            .dialogs
+            .retain(|_, dialog| visible_text(&dialog.text).trim().chars().count() >= min_chars);
+        let dropped = before - subtitle.dialogs.len();
+        if dropped > 0 {
+            self.note(format!(
+                "'{}': dropped {} cues shorter than {} characters",
+                subtitle.path, dropped, min_chars
+            ));
+        }
+    }
+
+    /// Fails on the first cue, in file order, that starts before zero, ends
+    /// before it starts or starts more than `tolerance` ms before the cue
+    /// above it.
+    fn check_strict(subtitle: &Subtitle, tolerance: i64) -> Result<(), MergeError> {
+        let mut cues: Vec<(&i64, &Dialog)> = subtitle.dialogs.iter().collect();
+        cues.sort_by_key(|(_, dialog)| dialog.line);
+        let mut previous_start = None;
+        for (&start, dialog) in cues {
+            let problem = if start < 0 {
+                Some(format!(
+                    "starts at {}, before zero",
+                    format_timestamp(start)
+                ))
+            } else if dialog.end < start {
+                Some(format!(
+                    "ends at {} before it starts at {}",
+                    format_timestamp(dialog.end),
+                    format_timestamp(start)
+                ))
+            } else {
+                previous_start
+                    .filter(|&previous| start < previous - tolerance)
+                    .map(|previous| {
+                        format!(
+                            "starts at {}, before the previous cue's start at {}",
+                            format_timestamp(start),
+                            format_timestamp(previous)
+                        )
+                    })
+            };
+            if let Some(problem) = problem {
+                return Err(MergeError::Parse(format!(
+                    "'{}' line {}: cue {} (--strict-timestamps)",
+                    subtitle.path, dialog.line, problem
+                )));
+            }
+            previous_start = Some(start);
+        }
+        Ok(())
+    }
+
+    /// Reports cues that end before they start, swapping their times if
+    /// `fix_swapped` is set, and cues that have no duration.
+    fn check_durations(&self, subtitle: &mut Subtitle) {
+        let mut swapped = 0;
+        let mut zero = 0;
+        for dialogs in [&mut subtitle.dialogs, &mut subtitle.comments] {
+            for (start, dialog) in std::mem::take(dialogs) {
+                if dialog.end == start {
+                    zero += 1;
+                }
+                if dialog.end < start {
+                    swapped += 1;
+                    if self.fix_swapped {
+                        let end = start;
+                        insert_dialog(dialogs, dialog.end, Dialog { end, ..dialog });
+                        continue;
+                    }
+                }
+                insert_dialog(dialogs, start, dialog);
+            }
+        }
+        if swapped > 0 {
+            if self.fix_swapped {
+                self.note(format!(
+                    "'{}': fixed {} cues ending before they start",
+                    subtitle.path, swapped
+                ));
+            } else {
+                self.note(format!(
+                    "Warning: '{}': {} cues end before they start; use --fix-swapped to swap their times",
+                    subtitle.path, swapped
+                ));
+            }
+        }
+        if zero > 0 {
+            self.note(format!(
+                "Warning: '{}': {} cues have zero duration",
+                subtitle.path, zero
+            ));
+        }
+    }
+
+    /// Prints an informational message or warning to stderr, unless
+    /// `--quiet` asks for errors only.
+    pub fn note(&self, message: String) {
+        if !self.quiet {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// Changes the output path once the inputs are loaded, for names that
+    /// depend on them such as `--output-template`.
+    pub fn set_output(&mut self, path: impl AsRef<Path>) {
+        self.output_path = path.as_ref().to_path_buf();
+    }
+
+    /// Lines written as a comment at the top of the output: a leading block
+    /// in SRT, a `NOTE` in WebVTT and `;` lines in ASS.
+    pub fn set_header_comment(&mut self, lines: Vec<String>) {
+        self.header_comment = lines;
+    }
+
+    /// Maps every start and end time of `subtitle` through `transform`.
+    fn retime(subtitle: &mut Subtitle, transform: impl Fn(i64) -> i64) {
+        for dialogs in [&mut subtitle.dialogs, &mut subtitle.comments] {
+            for (start, dialog) in std::mem::take(dialogs) {
+                let end = transform(dialog.end);
+                insert_dialog(dialogs, transform(start), Dialog { end, ..dialog });
+            }
+        }
+    }
+
+    fn merged_cues(&self) -> Vec<MergedCue> {
+        let mut timestamps: Vec<i64> = self
+            .subtitles
+            .iter()
+            .flat_map(|sub| sub.dialogs.keys().chain(sub.comments.keys()).cloned())
+            .collect();
+        timestamps.sort_unstable();
+        timestamps.dedup();
+
+        let mut cues = Vec::new();
+        for timestamp in timestamps {
+            for (index, subtitle) in self.subtitles.iter().enumerate() {
+                let events = [(&subtitle.dialogs, false), (&subtitle.comments, true)];
+                for (dialogs, comment) in events {
+                    if let Some(dialog) = dialogs.get(&timestamp) {
+                        cues.push(MergedCue {
+                            start: timestamp,
+                            end: dialog.end,
+                            parts: vec![CuePart {
+                                source: index,
+                                start: timestamp,
+                                text: dialog.text.clone(),
+                                style: dialog.style.clone(),
+                                index: dialog.index,
+                            }],
+                            comment,
+                            marker: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut cues = match self.strategy {
+            MergeStrategy::Sequential => cues,
+            MergeStrategy::Vertical => Self::bundle(cues, self.bundle_window),
+            MergeStrategy::Overwrite => {
+                let priority: Vec<usize> = if self.priority.is_empty() {
+                    (0..self.subtitles.len()).rev().collect()
+                } else {
+                    self.priority.clone()
+                };
+                Self::overwrite(cues, &priority)
+            }
+        };
+        if self.intersect {
+            cues.retain(|cue| {
+                let first = cue.parts[0].source;
+                cue.parts.iter().any(|part| part.source != first)
+            });
+        }
+        if let Some(keep) = self.dedup {
+            cues = Self::dedup_cues(cues, keep);
+        }
+        Self::sort_cues(&mut cues);
+        cues
+    }
+
+    /// Puts cues in output order: by start, then by their first source's
+    /// input order, dialogue before ASS comments, then by text. Cues with
+    /// the same start, even with the same end too, thus always come out
+    /// the same way.
+    fn sort_cues(cues: &mut [MergedCue]) {
+        cues.sort_by_cached_key(|cue| {
+            let source = cue.parts.first().map(|part| part.source);
+            (cue.start, source, cue.comment, cue.text())
+        });
+    }
+
+    /// Lowercased words of `text` without punctuation, so variants of one
+    /// caption compare equal.
+    fn dedup_key(text: &str) -> String {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Collapses near-identical parts within a stacked cue, then
+    /// near-identical cues whose times overlap, keeping `keep`'s choice of
+    /// text. A collapsed cue spans both originals.
+    fn dedup_cues(cues: Vec<MergedCue>, keep: KeepVariant) -> Vec<MergedCue> {
+        let mut result: Vec<MergedCue> = Vec::with_capacity(cues.len());
+        // Latest kept cue for each key.
+        let mut by_key: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        for mut cue in cues {
+            let mut parts: Vec<CuePart> = Vec::with_capacity(cue.parts.len());
+            for part in cue.parts {
+                let key = Self::dedup_key(&part.text);
+                match parts
+                    .iter_mut()
+                    .find(|kept| Self::dedup_key(&kept.text) == key)
+                {
+                    Some(kept) if keep.prefers(&kept.text, &part.text) => *kept = part,
+                    Some(_) => {}
+                    None => parts.push(part),
+                }
+            }
+            cue.parts = parts;
+
+            if cue.comment || cue.marker.is_some() {
+                result.push(cue);
+                continue;
+            }
+            let key = Self::dedup_key(&cue.text());
+            match by_key.get(&key).map(|&i| &mut result[i]) {
+                Some(kept) if kept.end > cue.start => {
+                    kept.end = kept.end.max(cue.end);
+                    if keep.prefers(&kept.text(), &cue.text()) {
+                        kept.parts = cue.parts;
+                    }
+                }
+                _ => {
+                    by_key.insert(key, result.len());
+                    result.push(cue);
+                }
+            }
+        }
+        result
+    }
+
+    /// Trims cues wherever a cue from a higher-priority source overlaps
+    /// them, so only the winning text is shown. `priority` lists source
+    /// indices from highest to lowest priority.
+    fn overwrite(cues: Vec<MergedCue>, priority: &[usize]) -> Vec<MergedCue> {
+        let (mut result, dialogs): (Vec<_>, Vec<_>) = cues.into_iter().partition(|cue| cue.comment);
+
+        // Union of the intervals claimed by higher-priority sources so far,
+        // kept sorted and non-overlapping.
+        let mut claimed: Vec<(i64, i64)> = Vec::new();
+        for &source in priority {
+            let own: Vec<&MergedCue> = dialogs
+                .iter()
+                .filter(|cue| cue.parts[0].source == source)
+                .collect();
+            for cue in &own {
+                let first = claimed.partition_point(|&(_, end)| end <= cue.start);
+                let overlapping = claimed[first..]
+                    .iter()
+                    .take_while(|&&(start, _)| start < cue.end);
+                let mut start = cue.start;
+                let mut trimmed = false;
+                for &(claimed_start, claimed_end) in overlapping {
+                    trimmed = true;
+                    if claimed_start > start {
+                        result.push(MergedCue {
+                            start,
+                            end: claimed_start,
+                            ..(*cue).clone()
+                        });
+                    }
+                    start = start.max(claimed_end);
+                }
+                if !trimmed || start < cue.end {
+                    result.push(MergedCue {
+                        start,
+                        ..(*cue).clone()
+                    });
+                }
+            }
+
+            claimed.extend(own.iter().map(|cue| (cue.start, cue.end)));
+            claimed.sort_unstable();
+            let mut merged: Vec<(i64, i64)> = Vec::with_capacity(claimed.len());
+            for (start, end) in claimed {
+                match merged.last_mut() {
+                    Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                    _ => merged.push((start, end)),
+                }
+            }
+            claimed = merged;
+        }
+
+        result.sort_by_key(|cue| (cue.start, cue.parts[0].source));
+        result
+    }
+
+    /// Stacks cues whose starts fall within `window` ms of the first cue of
+    /// their group into a single cue, regardless of where they end.
+    fn bundle(cues: Vec<MergedCue>, window: i64) -> Vec<MergedCue> {
+        let mut bundled: Vec<MergedCue> = Vec::new();
+        let mut group_start = None;
+        for cue in cues {
+            if cue.comment {
+                bundled.push(cue);
+                continue;
+            }
+            let group = bundled.iter_mut().rev().find(|other| !other.comment);
+            match (group, group_start) {
+                (Some(group), Some(start)) if cue.start - start <= window => {
+                    group.end = group.end.max(cue.end);
+                    group.parts.extend(cue.parts);
+                    group.parts.sort_by_key(|part| part.source);
+                }
+                _ => {
+                    group_start = Some(cue.start);
+                    bundled.push(cue);
+                }
+            }
+        }
+        bundled
+    }
+
+    fn summarize(&self, cues: &[MergedCue]) -> MergeSummary {
+        let mut per_source: Vec<(String, usize)> = self
+            .subtitles
+            .iter()
+            .map(|sub| (sub.path.clone(), 0))
+            .collect();
+        for part in cues.iter().flat_map(|cue| &cue.parts) {
+            per_source[part.source].1 += 1;
+        }
+        let fast_cues = match self.reading_speed_limit {
+            Some(limit) => cues
+                .iter()
+                .enumerate()
+                .filter_map(|(i, cue)| {
+                    let seconds = (cue.end - cue.start).max(1) as f64 / 1000.0;
+                    let cps = visible_len(&cue.text()) as f64 / seconds;
+                    (cps > limit).then_some((i + 1, cps))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        MergeSummary {
+            total: cues.len(),
+            first_start: cues.first().map_or(0, |cue| cue.start),
+            last_end: cues.iter().map(|cue| cue.end).max().unwrap_or(0),
+            per_source,
+            fast_cues,
+        }
+    }
+
+    /// Splits `cues` at the configured cue count and duration limits. Without
+    /// limits, or without cues, the whole merge is a single chunk.
+    fn chunks(&self, cues: &[MergedCue]) -> Vec<Vec<MergedCue>> {
+        if cues.is_empty() {
+            return vec![Vec::new()];
+        }
+        let mut chunks: Vec<Vec<MergedCue>> = Vec::new();
+        for cue in cues {
+            let starts_new = match chunks.last() {
+                None => true,
+                Some(chunk) => {
+                    self.chunk_cues.is_some_and(|max| chunk.len() >= max)
+                        || self
+                            .chunk_duration
+                            .is_some_and(|max| cue.end - chunk[0].start > max)
+                }
+            };
+            if starts_new {
+                chunks.push(Vec::new());
+            }
+            chunks.last_mut().unwrap().push(cue.clone());
+        }
+        if let Some(lead_in) = self.rebase {
+            for chunk in &mut chunks {
+                Self::rebase_cues(chunk, lead_in);
+            }
+        }
+        chunks
+    }
+
+    /// Shifts `cues` so the earliest one starts at `lead_in`, keeping their
+    /// relative spacing.
+    fn rebase_cues(cues: &mut [MergedCue], lead_in: i64) {
+        let Some(earliest) = cues.iter().map(|cue| cue.start).min() else {
+            return;
+        };
+        for cue in cues {
+            cue.start += lead_in - earliest;
+            cue.end += lead_in - earliest;
+        }
+    }
+
+    /// Warns about cues ending after the media does, a sign of a wrong
+    /// `--fps` or offset, and with `--clamp-to-media` cuts them at its end,
+    /// dropping those that would start there or later.
+    fn check_media_duration(&self, cues: &mut Vec<MergedCue>, duration: i64) {
+        let late: Vec<&MergedCue> = cues.iter().filter(|cue| cue.end > duration).collect();
+        let Some(last_end) = late.iter().map(|cue| cue.end).max() else {
+            return;
+        };
+        self.note(format!(
+            "Warning: {} cues end after the media's {}, the last at {}; check --fps and the offsets{}",
+            late.len(),
+            format_timestamp(duration),
+            format_timestamp(last_end),
+            if self.clamp_to_media {
+                "; clamped to the media"
+            } else {
+                ""
+            }
+        ));
+        if self.clamp_to_media {
+            cues.retain(|cue| cue.start < duration);
+            for cue in cues.iter_mut() {
+                cue.end = cue.end.min(duration);
+            }
+        }
+    }
+
+    /// Moves each cue start and end to the nearest shot change within the
+    /// snap tolerance. A boundary stays put if there is none, or if moving it
+    /// would leave the cue without a duration.
+    fn snap_cues(&self, cues: &mut [MergedCue]) {
+        let nearest = |time: i64| {
+            let i = self.snap_points.partition_point(|&point| point < time);
+            [i.checked_sub(1), Some(i)]
+                .into_iter()
+                .flatten()
+                .filter_map(|i| self.snap_points.get(i).copied())
+                .min_by_key(|&point| (point - time).abs())
+                .filter(|&point| (point - time).abs() <= self.snap_tolerance)
+        };
+        for cue in cues {
+            if let Some(start) = nearest(cue.start).filter(|&start| start < cue.end) {
+                cue.start = start;
+            }
+            if let Some(end) = nearest(cue.end).filter(|&end| end > cue.start) {
+                cue.end = end;
+            }
+        }
+    }
+
+    /// Splits cues of more than `max_lines` lines into as few consecutive
+    /// cues as needed, sharing the time range evenly. Lines are spread evenly
+    /// too, earlier pieces taking the extra line of an odd count.
+    fn split_tall_cues(cues: Vec<MergedCue>, max_lines: usize) -> Vec<MergedCue> {
+        let mut result = Vec::with_capacity(cues.len());
+        for cue in cues {
+            let lines: Vec<(&CuePart, &str)> = cue
+                .parts
+                .iter()
+                .flat_map(|part| {
+                    part.text
+                        .split('\n')
+                        .flat_map(|line| line.split("\\N"))
+                        .map(move |line| (part, line))
+                })
+                .collect();
+            if lines.len() <= max_lines {
+                result.push(cue);
+                continue;
+            }
+
+            let pieces = lines.len().div_ceil(max_lines);
+            let duration = cue.end - cue.start;
+            let mut remaining = lines.as_slice();
+            for k in 0..pieces {
+                let size = lines.len() / pieces + usize::from(k < lines.len() % pieces);
+                let (piece, rest) = remaining.split_at(size);
+                remaining = rest;
+
+                let mut parts: Vec<CuePart> = Vec::new();
+                for &(part, line) in piece {
+                    match parts.last_mut() {
+                        Some(last) if last.source == part.source => {
+                            last.text.push('\n');
+                            last.text.push_str(line);
+                        }
+                        _ => parts.push(CuePart {
+                            text: line.to_string(),
+                            ..part.clone()
+                        }),
+                    }
+                }
+                result.push(MergedCue {
+                    start: cue.start + duration * k as i64 / pieces as i64,
+                    end: cue.start + duration * (k as i64 + 1) / pieces as i64,
+                    parts,
+                    ..cue.clone()
+                });
+            }
+        }
+        result
+    }
+
+    /// `--fill-gaps`: extends each cue to the start of the first cue
+    /// starting at or after its end, by at most `fill_max` ms. Cues that
+    /// already overlap the next keep their end, as does the last cue, and
+    /// ASS comments are neither extended nor extended to.
+    fn fill_gaps_between(&self, cues: &mut [MergedCue]) {
+        let starts: Vec<i64> = cues
+            .iter()
+            .filter(|cue| !cue.comment)
+            .map(|cue| cue.start)
+            .collect();
+        for cue in cues.iter_mut().filter(|cue| !cue.comment) {
+            let Some(&next) = starts.get(starts.partition_point(|&start| start < cue.end)) else {
+                continue;
+            };
+            cue.end = match self.fill_max {
+                Some(max) => next.min(cue.end + max),
+                None => next,
+            };
+        }
+    }
+
+    /// Fills every gap longer than `gap` ms between a cue's end and the next
+    /// start with a marker cue carrying the configured placeholder text.
+    fn insert_silence_markers(&self, cues: Vec<MergedCue>, gap: i64) -> Vec<MergedCue> {
+        let mut result = Vec::with_capacity(cues.len());
+        let mut covered_until: Option<i64> = None;
+        for cue in cues {
+            if let Some(end) = covered_until.filter(|&end| cue.start - end > gap) {
+                result.push(MergedCue {
+                    start: end,
+                    end: cue.start,
+                    parts: Vec::new(),
+                    comment: false,
+                    marker: Some(self.silence_text.clone()),
+                });
+            }
+            covered_until = Some(covered_until.map_or(cue.end, |end| end.max(cue.end)));
+            result.push(cue);
+        }
+        result
+    }
+
+    /// `out.srt` becomes `out-1.srt`, `out-2.srt`, ... when chunking.
+    fn chunk_path(&self, index: usize) -> PathBuf {
+        let stem = self
+            .output_path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        let mut name = format!("{}-{}", stem, index + 1);
+        if let Some(extension) = self.output_path.extension() {
+            name.push('.');
+            name.push_str(&extension.to_string_lossy());
+        }
+        self.output_path.with_file_name(name)
+    }
+
+    /// `--format-out` if given, else `ass` or `vtt` by the output
+    /// extension, else `srt`.
+    fn target_format(&self) -> String {
+        if let Some(format) = &self.output_format {
+            return format.clone();
+        }
+        let extension = self
+            .output_path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some(format @ ("ass" | "vtt")) => format.to_string(),
+            _ => "srt".to_string(),
+        }
+    }
+
+    /// Writes the merged inputs to the output path. Any number of inputs
+    /// works: none fails with [`MergeError::Empty`], like a merge that
+    /// leaves no cues, and a single one is written on its own in the output
+    /// format, with its transforms applied, as when `--keep-only-sources`
+    /// or `--dedup-inputs` leave one.
+    pub fn merge(&self) -> Result<MergeSummary, MergeError> {
+        if self.subtitles.is_empty() {
+            return Err(MergeError::Empty);
+        }
+        let mut cues = self.merged_cues();
+
+        let format = self.target_format();
+        if self.style_from.is_some() && format != "ass" {
+            return Err(MergeError::Validation(format!(
+                "--style-from writes styled ASS, but '{}' is {}; name the output .ass or pass --format-out ass",
+                self.output_path.display(),
+                format
+            )));
+        }
+        if format != "ass" {
+            cues.retain(|cue| !cue.comment);
+        }
+        if let Some(duration) = self.media_duration {
+            self.check_media_duration(&mut cues, duration);
+        }
+        if cues.is_empty() {
+            return Err(MergeError::Empty);
+        }
+        if !self.snap_points.is_empty() {
+            self.snap_cues(&mut cues);
+            // Snapping can give cues that started apart the same start.
+            Self::sort_cues(&mut cues);
+        }
+        if let Some(n) = self.sample_every {
+            cues = cues.into_iter().step_by(n).collect();
+        }
+        if self.fill_gaps {
+            self.fill_gaps_between(&mut cues);
+        }
+        if let Some(max_lines) = self.max_lines {
+            cues = Self::split_tall_cues(cues, max_lines);
+        }
+        if let Some(gap) = self.silence_gap {
+            cues = self.insert_silence_markers(cues, gap);
+        }
+
+        let chunked = self.chunk_cues.is_some() || self.chunk_duration.is_some();
+        // `-` writes the merge to stdout; it names no file to check or to
+        // derive chunk names from.
+        let to_stdout = self.output_path == Path::new("-");
+        if to_stdout && chunked {
+            return Err(MergeError::Validation(
+                "--chunk-cues and --chunk-duration write several files and need an output path, not '-'".to_string(),
+            ));
+        }
+        // Rebasing the whole output up front keeps the summary in step with
+        // the file; chunks are rebased one by one below.
+        if let (Some(lead_in), false) = (self.rebase, chunked) {
+            Self::rebase_cues(&mut cues, lead_in);
+        }
+        let chunks = self.chunks(&cues);
+        let mut outputs = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let path = if chunked {
+                self.chunk_path(i)
+            } else {
+                self.output_path.clone()
+            };
+            let output = match format.as_str() {
+                "ass" => self.format_ass(chunk),
+                "vtt" => self.format_vtt(chunk),
+                _ => self.format_srt(chunk),
+            };
+            if !to_stdout {
+                self.check_output_path(&path)?;
+            }
+            if let Some(limit) = self
+                .max_output_bytes
+                .filter(|&limit| output.len() as u64 > limit)
+            {
+                return Err(MergeError::Validation(format!(
+                    "Output '{}' would be {} bytes, over the --max-output-bytes limit of {}; nothing was written",
+                    path.display(),
+                    output.len(),
+                    limit
+                )));
+            }
+            outputs.push((path, output));
+        }
+        if to_stdout {
+            let mut stdout = std::io::stdout().lock();
+            for (_, output) in &outputs {
+                stdout
+                    .write_all(output.as_bytes())
+                    .and_then(|()| stdout.flush())
+                    .map_err(|e| MergeError::Io(format!("Cannot write to stdout: {}", e)))?;
+            }
+        } else {
+            for (path, output) in &outputs {
+                File::create(path)
+                    .and_then(|mut file| file.write_all(output.as_bytes()))
+                    .map_err(|e| {
+                        MergeError::Io(format!("Cannot write '{}': {}", path.display(), e))
+                    })?;
+            }
+        }
+
+        if let Some(path) = &self.attribution_path {
+            File::create(path)
+                .and_then(|mut file| {
+                    file.write_all(self.format_attribution(&chunks, chunked).as_bytes())
+                })
+                .map_err(|e| MergeError::Io(format!("Cannot write '{}': {}", path.display(), e)))?;
+        }
+
+        for (path, _) in outputs.iter().filter(|_| !to_stdout) {
+            self.note(format!("'{}' created successfully.", path.display()));
+        }
+        Ok(self.summarize(&cues))
+    }
+
+    /// Maps each output cue's number, as [`Self::cue_numbers`] gives it, to
+    /// the input it came from, or to the list of inputs for stacked cues, as
+    /// a JSON object. Every chunk numbers its cues afresh, so chunked output
+    /// gets one such object per chunk file, keyed by the file's path.
+    fn format_attribution(&self, chunks: &[Vec<MergedCue>], chunked: bool) -> String {
+        if !chunked {
+            return format!("{{\n{}\n}}\n", self.attribution_entries(&chunks[0], "  "));
+        }
+        let files: Vec<String> = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                format!(
+                    "  {}: {{\n{}\n  }}",
+                    json_string(&self.chunk_path(i).to_string_lossy()),
+                    self.attribution_entries(chunk, "    ")
+                )
+            })
+            .collect();
+        format!("{{\n{}\n}}\n", files.join(",\n"))
+    }
+
+    /// The `"number": input` lines of [`Self::format_attribution`], each
+    /// behind `indent`.
+    fn attribution_entries(&self, cues: &[MergedCue], indent: &str) -> String {
+        let entries: Vec<String> = cues
+            .iter()
+            .zip(self.cue_numbers(cues))
+            .map(|(cue, number)| {
+                let paths: Vec<String> = cue
+                    .parts
+                    .iter()
+                    .map(|part| json_string(&self.subtitles[part.source].path))
+                    .collect();
+                let value = match paths.as_slice() {
+                    [path] => path.clone(),
+                    _ => format!("[{}]", paths.join(", ")),
+                };
+                format!("{}\"{}\": {}", indent, number, value)
+            })
+            .collect();
+        entries.join(",\n")
+    }
+
+    /// The merged cues without any output formatting, for the `serve`
+    /// preview: each cue's times in ms, its stacked text and the indices of
+    /// the inputs it came from, as JSON.
+    pub fn format_cues_json(&self) -> String {
+        let cues: Vec<String> = self
+            .merged_cues()
+            .iter()
+            .map(|cue| {
+                let sources: Vec<String> = cue.parts.iter().map(|p| p.source.to_string()).collect();
+                format!(
+                    "    {{\"start\": {}, \"end\": {}, \"text\": {}, \"sources\": [{}]}}",
+                    cue.start,
+                    cue.end,
+                    json_string(&cue.text()),
+                    sources.join(", ")
+                )
+            })
+            .collect();
+        format!("{{\n  \"cues\": [\n{}\n  ]\n}}\n", cues.join(",\n"))
+    }
+
+    /// Refuses to overwrite one of the inputs unless `force` is set.
+    fn check_output_path(&self, path: &Path) -> Result<(), MergeError> {
+        if self.force {
+            return Ok(());
+        }
+        let Ok(output) = std::fs::canonicalize(path) else {
+            return Ok(());
+        };
+        for subtitle in &self.subtitles {
+            if std::fs::canonicalize(&subtitle.path).is_ok_and(|input| input == output) {
+                return Err(MergeError::Validation(format!(
+                    "Output '{}' is the same file as input '{}'; use --force to overwrite it",
+                    path.display(),
+                    subtitle.path
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Formats the first `count` merged cues for a quick look in the terminal,
+    /// coloring each source differently when `color` is set.
+    pub fn format_preview(&self, count: usize, color: bool) -> String {
+        const SOURCE_COLORS: [&str; 4] = ["33", "36", "32", "35"];
+
+        let mut preview = String::new();
+        for (i, cue) in self.merged_cues().iter().take(count).enumerate() {
+            let timing = format!(
+                "{} --> {}",
+                format_timestamp(cue.start),
+                format_timestamp(cue.end)
+            );
+            let paint = |text: &str, code: &str| {
+                if color {
+                    format!("\x1b[{}m{}\x1b[0m", code, text)
+                } else {
+                    text.to_string()
+                }
+            };
+            let timing = if cue.comment {
+                format!("{} (comment)", timing)
+            } else {
+                timing
+            };
+            preview.push_str(&format!("{:>4}  {}\n", i + 1, paint(&timing, "2")));
+            for part in &cue.parts {
+                let code = SOURCE_COLORS[part.source % SOURCE_COLORS.len()];
+                for line in part.text.lines() {
+                    preview.push_str(&format!("      {}\n", paint(line, code)));
+                }
+            }
+        }
+        preview
+    }
+
+    /// A TSV row for each cue of the first input with how much of it the
+    /// other inputs' cues cover, in ms and as a fraction of its length.
+    /// Where other cues overlap each other, their time counts once.
+    pub fn format_overlap_report(&self) -> String {
+        let mut report = String::from("cue\tstart\tend\tduration_ms\toverlap_ms\toverlap\n");
+        let Some((reference, others)) = self.subtitles.split_first() else {
+            return report;
+        };
+        let mut others: Vec<(i64, i64)> = others
+            .iter()
+            .flat_map(|subtitle| &subtitle.dialogs)
+            .map(|(&start, dialog)| (start, dialog.end))
+            .collect();
+        others.sort_unstable();
+        let mut covered: Vec<(i64, i64)> = Vec::with_capacity(others.len());
+        for (start, end) in others {
+            match covered.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => covered.push((start, end)),
+            }
+        }
+        let mut reference: Vec<(&i64, &Dialog)> = reference.dialogs.iter().collect();
+        reference.sort_unstable_by_key(|&(&start, dialog)| (start, dialog.end));
+        for (i, (&start, dialog)) in reference.into_iter().enumerate() {
+            let overlap: i64 = covered
+                .iter()
+                .map(|&(other_start, other_end)| {
+                    (dialog.end.min(other_end) - start.max(other_start)).max(0)
+                })
+                .sum();
+            let duration = dialog.end - start;
+            let fraction = if duration > 0 {
+                overlap as f64 / duration as f64
+            } else {
+                0.0
+            };
+            report.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{:.3}\n",
+                i + 1,
+                format_timestamp(start),
+                format_timestamp(dialog.end),
+                duration,
+                overlap,
+                fraction
+            ));
+        }
+        report
+    }
+
+    /// Describes for each merged cue which source cues it groups and how far
+    /// their starts spread, or how far away the next cue started if it
+    /// grouped nothing, against the bundle window.
+    pub fn format_window_report(&self) -> String {
+        let cues = self.merged_cues();
+        let mut report = String::new();
+        for (i, cue) in cues.iter().enumerate() {
+            let reason = match cue.parts.len() {
+                0 => continue,
+                1 => match cues[i + 1..].iter().find(|next| !next.comment) {
+                    Some(next) => format!(
+                        "not grouped; the next cue starts {}ms later, window {}ms",
+                        next.start - cue.start,
+                        self.bundle_window
+                    ),
+                    None => "not grouped; last cue".to_string(),
+                },
+                n => {
+                    let last = cue.parts.iter().map(|part| part.start).max().unwrap();
+                    format!(
+                        "grouped {} cues starting within {}ms, window {}ms",
+                        n,
+                        last - cue.start,
+                        self.bundle_window
+                    )
+                }
+            };
+            report.push_str(&format!(
+                "cue {}  {} --> {}: {}\n",
+                i + 1,
+                format_timestamp(cue.start),
+                format_timestamp(cue.end),
+                reason
+            ));
+            for part in &cue.parts {
+                report.push_str(&format!(
+                    "    {}  {}  +{}ms\n",
+                    format_timestamp(part.start),
+                    self.subtitles[part.source].path,
+                    part.start - cue.start
+                ));
+            }
+        }
+        report
+    }
+
+    /// With `keep_styling_map`, wraps each part in `<b>`/`<i>` when its ASS
+    /// style is bold or italic, since SRT has no style table.
+    fn srt_text(&self, cue: &MergedCue) -> String {
+        let text = self.srt_styled_text(cue);
+        match &self.vocab {
+            Some(vocab) => vocab.mark(&text, &vocab.srt),
+            None => text,
+        }
+    }
+
+    fn srt_styled_text(&self, cue: &MergedCue) -> String {
+        if !self.keep_styling_map || cue.parts.is_empty() {
+            return self.blank_lines_trimmed(&static_tags(&self.stacked_text(cue, "srt")), "\n");
+        }
+        let parts: Vec<String> = cue
+            .parts
+            .iter()
+            .map(|part| {
+                let style = part
+                    .style
+                    .as_ref()
+                    .and_then(|name| self.subtitles[part.source].styles.get(name));
+                let mut text = self.part_text(part, "srt");
+                if let Some(style) = style {
+                    if style.italic {
+                        text = format!("<i>{}</i>", text);
+                    }
+                    if style.bold {
+                        text = format!("<b>{}</b>", text);
+                    }
+                }
+                text
+            })
+            .collect();
+        self.blank_lines_trimmed(&static_tags(&stack_parts(parts)), "\n")
+    }
+
+    /// The text of `part` with its tags in the terms of the `format` it is
+    /// written as. Each input's tags are converted by its own format, so
+    /// mixed inputs come out alike: in SRT and WebVTT, ASS override tags
+    /// become HTML and `\N` breaks real ones; in ASS, HTML tags become
+    /// override tags; and WebVTT, which has no `<font>`, loses font tags.
+    /// ASS `\h` hard spaces become `hard_space` in SRT and WebVTT, after
+    /// `collapse_nbsp` has made any no-break spaces already there plain.
+    fn part_text(&self, part: &CuePart, format: &str) -> String {
+        let source = self.subtitles[part.source].format.as_str();
+        let text = if self.collapse_nbsp && format != "ass" {
+            part.text.replace('\u{a0}', " ")
+        } else {
+            part.text.clone()
+        };
+        let hard_space = self.hard_space.to_string();
+        match (source, format) {
+            ("ass", "srt") => ass_tags_to_html(&static_tags(&text))
+                .replace("\\N", "\n")
+                .replace("\\n", "\n")
+                .replace("\\h", &hard_space),
+            ("ass", "vtt") => strip_font_tags(&ass_tags_to_html(&text)).replace("\\h", &hard_space),
+            ("srt" | "vtt", "ass") => html_tags_to_ass(&text),
+            (_, "vtt") => strip_font_tags(&text),
+            _ => text,
+        }
+    }
+
+    /// The text of `cue` as written in `format`, each part converted by
+    /// [`Self::part_text`] and its inline tags kept to itself; see
+    /// [`stack_parts`].
+    fn stacked_text(&self, cue: &MergedCue, format: &str) -> String {
+        match &cue.marker {
+            Some(marker) => marker.clone(),
+            None => stack_parts(cue.parts.iter().map(|part| self.part_text(part, format))),
+        }
+    }
+
+    /// With `--trim-whitespace-lines`, drops the blank lines between the
+    /// `separator`-delimited lines of `text`, unless no other line is left.
+    fn blank_lines_trimmed(&self, text: &str, separator: &str) -> String {
+        if !self.trim_whitespace_lines {
+            return text.to_string();
+        }
+        let lines: Vec<&str> = text
+            .split(separator)
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        if lines.is_empty() {
+            text.to_string()
+        } else {
+            lines.join(separator)
+        }
+    }
+
+    /// Joins the lines of `text`, including ASS `\N` breaks, with `separator`.
+    fn single_line(text: &str, separator: &str) -> String {
+        text.split('\n')
+            .flat_map(|line| line.split("\\N"))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// What each source's cue numbers are shifted by without renumbering:
+    /// the sum of the highest numbers of the sources before it. A source
+    /// without numbers counts its cues instead.
+    fn index_offsets(&self) -> Vec<u64> {
+        let mut offsets = Vec::with_capacity(self.subtitles.len());
+        let mut offset = 0;
+        for subtitle in &self.subtitles {
+            offsets.push(offset);
+            let dialogs = subtitle.dialogs.values().chain(subtitle.comments.values());
+            offset += dialogs
+                .clone()
+                .filter_map(|dialog| dialog.index)
+                .max()
+                .unwrap_or(dialogs.count() as u64);
+        }
+        offsets
+    }
+
+    /// Applies `--ms-sep` to an SRT or VTT timestamp.
+    fn ms_separated(&self, timestamp: String) -> String {
+        match self.ms_separator {
+            Some(separator) => timestamp.replace([',', '.'], &separator.to_string()),
+            None => timestamp,
+        }
+    }
+
+    /// The text lines of an SRT or VTT cue, each ending with a newline.
+    /// With `--diff-friendly`, trailing spaces and blank lines are dropped,
+    /// so exactly one blank line follows every cue, even an empty one.
+    fn text_lines(&self, text: &str) -> String {
+        if !self.diff_friendly {
+            return format!("{}\n", text);
+        }
+        text.lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+
+    /// The number of each cue in an SRT file of `cues`: its place from 1, or
+    /// with `--no-renumber` its original number shifted by
+    /// [`Self::index_offsets`].
+    fn cue_numbers(&self, cues: &[MergedCue]) -> Vec<u64> {
+        let offsets = self.index_offsets();
+        let mut number = 0;
+        cues.iter()
+            .map(|cue| {
+                // Cues without a number of their own continue from the last.
+                number = match cue.parts.first() {
+                    Some(CuePart {
+                        index: Some(index),
+                        source,
+                        ..
+                    }) if !self.renumber => index + offsets[*source],
+                    _ => number + 1,
+                };
+                number
+            })
+            .collect()
+    }
+
+    fn format_srt(&self, cues: &[MergedCue]) -> String {
+        let output_lines: Vec<String> = cues
+            .iter()
+            .zip(self.cue_numbers(cues))
+            .map(|(cue, number)| {
+                format!(
+                    "{:0width$}\n{} --> {}\n{}",
+                    number,
+                    self.ms_separated(format_timestamp(cue.start)),
+                    self.ms_separated(format_timestamp(cue.end)),
+                    self.text_lines(&match &self.one_line {
+                        Some(separator) => Self::single_line(&self.srt_text(cue), separator),
+                        None => self.srt_text(cue),
+                    }),
+                    width = self.index_width
+                )
+            })
+            .collect();
+        let mut output = output_lines.join("\n");
+        if (self.final_newline || self.diff_friendly) && !output.is_empty() {
+            output.push('\n');
+        }
+        // SRT has no comments, but a block without a timing line is skipped
+        // by readers, this one included.
+        if !self.header_comment.is_empty() {
+            output.insert_str(0, &format!("{}\n\n", self.header_comment.join("\n")));
+        }
+        output
+    }
+
+    fn format_vtt(&self, cues: &[MergedCue]) -> String {
+        // Every timestamp of a file takes the same form, so the hour is only
+        // left out if all of them can do without it.
+        let hours = !self.vtt_omit_hours || cues.iter().any(|cue| cue.end >= 3_600_000);
+        let output_lines: Vec<String> = cues
+            .iter()
+            .map(|cue| {
+                let text = self.stacked_text(cue, "vtt");
+                let mut timing = format!(
+                    "{} --> {}",
+                    self.ms_separated(format_vtt_timestamp(cue.start, hours)),
+                    self.ms_separated(format_vtt_timestamp(cue.end, hours))
+                );
+                let settings = vtt_settings(&text);
+                if !settings.is_empty() {
+                    timing.push(' ');
+                    timing.push_str(&settings);
+                }
+                let text = vtt_text(&text);
+                let text = match &self.vocab {
+                    Some(vocab) => vocab.mark(&text, &vocab.vtt),
+                    None => text,
+                };
+                format!(
+                    "{}\n{}",
+                    timing,
+                    self.text_lines(&self.blank_lines_trimmed(&text, "\n"))
+                )
+            })
+            .collect();
+        let mut output = String::from("WEBVTT\n\n");
+        if !self.header_comment.is_empty() {
+            output.push_str(&format!("NOTE\n{}\n\n", self.header_comment.join("\n")));
+        }
+        output.push_str(&output_lines.join("\n"));
+        if (self.final_newline || self.diff_friendly) && !output_lines.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Rounds each cue's times to centiseconds, then nudges them forward so
+    /// rounding never makes a cue empty, lets it start with or before the
+    /// previous cue, or overlap a previous cue it did not overlap before.
+    fn ass_times(&self, cues: &[MergedCue]) -> Vec<(i64, i64)> {
+        let mut times: Vec<(i64, i64)> = Vec::with_capacity(cues.len());
+        for (i, cue) in cues.iter().enumerate() {
+            let mut start = self.ass_rounding.centiseconds(cue.start);
+            let mut end = self.ass_rounding.centiseconds(cue.end);
+            if let (Some(previous), Some(&(previous_start, previous_end))) =
+                (i.checked_sub(1).map(|i| &cues[i]), times.last())
+            {
+                if cue.start > previous.start {
+                    start = start.max(previous_start + 1);
+                }
+                if cue.start >= previous.end {
+                    start = start.max(previous_end);
+                }
+            }
+            if cue.end > cue.start {
+                end = end.max(start + 1);
+            }
+            times.push((start, end));
+        }
+        times
+            .into_iter()
+            .map(|(start, end)| (start * 10, end * 10))
+            .collect()
+    }
+
+    fn format_ass(&self, cues: &[MergedCue]) -> String {
+        let mut output = String::from("[Script Info]\n");
+        for line in &self.header_comment {
+            output.push_str(&format!("; {}\n", line));
+        }
+        output.push_str("ScriptType: v4.00+\n");
+        if let Some((width, height)) = self.play_res {
+            output.push_str(&format!("PlayResX: {}\nPlayResY: {}\n", width, height));
+        }
+
+        // The `--style-from` script, then the first input to define a style
+        // name wins; events of a style no input defined (and SRT events)
+        // fall back to a built-in Default, which is only written when some
+        // event uses it.
+        let mut styles: Vec<&StyleLine> = Vec::new();
+        let style_lines = self
+            .style_from
+            .iter()
+            .chain(&self.subtitles)
+            .flat_map(|sub| &sub.style_lines);
+        for style in style_lines {
+            if !styles.iter().any(|(name, _)| *name == style.0) {
+                styles.push(style);
+            }
+        }
+        let style_of = |part: &CuePart| match &part.style {
+            Some(name) if styles.iter().any(|(defined, _)| defined == name) => name.clone(),
+            _ => "Default".to_string(),
+        };
+        output.push_str("\n[V4+ Styles]\nFormat: ");
+        output.push_str(&ASS_STYLE_COLUMNS.join(", "));
+        output.push('\n');
+        let needs_default = cues.iter().any(|cue| {
+            cue.parts.is_empty() || cue.parts.iter().any(|part| style_of(part) == "Default")
+        });
+        if (needs_default || styles.is_empty()) && !styles.iter().any(|(name, _)| name == "Default")
+        {
+            output.push_str(&format!("Style: {}\n", ASS_DEFAULT_STYLE.join(",")));
+        }
+        for (_, values) in &styles {
+            output.push_str(&format!("Style: {}\n", values.join(",")));
+        }
+        output.push_str(
+            "\n[Events]\n\
+             Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+        );
+        for (cue, (start, end)) in cues.iter().zip(self.ass_times(cues)) {
+            // With a layer or a position per source, stacked cues become one
+            // event per source so each track stays independently toggleable
+            // and placeable.
+            let split = self.layer_per_source || !self.positions.is_empty();
+            let events: Vec<(usize, String, String)> = if split && !cue.parts.is_empty() {
+                cue.parts
+                    .iter()
+                    .map(|part| {
+                        let layer = if self.layer_per_source {
+                            part.source
+                        } else {
+                            0
+                        };
+                        let text = self.part_text(part, "ass");
+                        let text = match self.positions.get(part.source) {
+                            Some(alignment) => format!("{{\\an{}}}{}", alignment, text),
+                            None => text,
+                        };
+                        (layer, style_of(part), text)
+                    })
+                    .collect()
+            } else {
+                let style = cue
+                    .parts
+                    .first()
+                    .map_or_else(|| "Default".to_string(), style_of);
+                vec![(0, style, self.stacked_text(cue, "ass"))]
+            };
+            for (layer, style, text) in events {
+                let text = match &self.vocab {
+                    Some(vocab) => vocab.mark(&text, &vocab.ass),
+                    None => text,
+                };
+                output.push_str(&format!(
+                    "{}: {},{},{},{},,0,0,0,,{}\n",
+                    if cue.comment { "Comment" } else { "Dialogue" },
+                    layer,
+                    format_ass_timestamp(start),
+                    format_ass_timestamp(end),
+                    style,
+                    self.blank_lines_trimmed(&text.replace('\n', "\\N"), "\\N")
+                ));
+            }
+        }
+        // Written after the events; the first input to have a section of
+        // a name wins.
+        if self.keep_unknown_sections {
+            let mut written: Vec<&str> = Vec::new();
+            for (name, lines) in self.subtitles.iter().flat_map(|sub| &sub.extra_sections) {
+                if written.iter().any(|other| other.eq_ignore_ascii_case(name)) {
+                    continue;
+                }
+                written.push(name);
+                output.push_str(&format!("\n[{}]\n", name));
+                for line in lines {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,61 @@
+//! `--anchor`: the second cue of `de.srt` starts 100ms after that of
+//! `en.srt`; anchoring one to the other shifts all of `de.srt` to match.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn preview(args: &[&str]) -> Output {
+    submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "9", "--merge-strategy", "vertical"])
+        .args(args)
+        .run()
+}
+
+fn stderr(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn anchored_moments_line_up() {
+    let preview = stderr(&preview(&["--anchor", "00:00:04,000=00:00:04,100"]));
+    assert!(
+        preview.contains(
+            "00:00:04,000 --> 00:00:06,250\n      Where are you going?\n      Wohin gehst du?\n"
+        ),
+        "{}",
+        preview
+    );
+    // The offset applies to the whole input, not only the anchored cue.
+    assert!(
+        preview.contains("00:00:00,900 --> 00:00:03,300\n      Guten Morgen.\n"),
+        "{}",
+        preview
+    );
+}
+
+#[test]
+fn anchor_times_are_scaled_with_their_input() {
+    let preview = stderr(&preview(&[
+        "--scale",
+        "1,2",
+        "--anchor",
+        "00:00:04,000=00:00:04,100",
+    ]));
+    assert!(
+        preview.contains("00:00:04,000 --> 00:00:08,400\n"),
+        "{}",
+        preview
+    );
+}
+
+#[test]
+fn one_anchor_per_later_input_at_most() {
+    let output = preview(&[["--anchor", "00:00:01,000=00:00:01,000"]; 2].concat());
+    assert_eq!(output.status.code(), Some(5));
+}
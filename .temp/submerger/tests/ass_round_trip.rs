@@ -0,0 +1,78 @@
+//! ASS→ASS fidelity: merging a styled, positioned file with itself (the
+//! duplicate is dropped by `--dedup-inputs`) must give back its PlayRes,
+//! styles and dialogue. Each section is compared on its own so a failure
+//! names the one that diverged.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+/// The lines of `[name]` up to the next section, without blank lines.
+fn section<'a>(content: &'a str, name: &str) -> Vec<&'a str> {
+    content
+        .lines()
+        .skip_while(|line| line.trim() != name)
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.trim().is_empty())
+        .collect()
+}
+
+/// Start, End, Style and Text of every `Dialogue:` line.
+fn dialogue(content: &str) -> Vec<Vec<String>> {
+    section(content, "[Events]")
+        .into_iter()
+        .filter_map(|line| line.strip_prefix("Dialogue:"))
+        .map(|event| {
+            let fields: Vec<&str> = event.trim_start().splitn(10, ',').collect();
+            [1, 2, 3, 9]
+                .iter()
+                .map(|&i| fields[i].to_string())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn ass_survives_a_merge_with_itself() {
+    let input_path = fixture("complex.ass");
+    let output_path = std::env::temp_dir().join("submerger-round-trip.ass");
+    let status = submerger()
+        .arg(&input_path)
+        .arg(&input_path)
+        .arg(&output_path)
+        .arg("--dedup-inputs")
+        .run();
+    assert!(
+        status.status.success(),
+        "{}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+
+    let input = fs::read_to_string(&input_path).unwrap();
+    let output = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(&output_path).ok();
+
+    let play_res = |content| {
+        section(content, "[Script Info]")
+            .into_iter()
+            .filter(|line| line.starts_with("PlayRes"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        play_res(&output),
+        play_res(&input),
+        "[Script Info] PlayRes diverged"
+    );
+    assert_eq!(
+        section(&output, "[V4+ Styles]"),
+        section(&input, "[V4+ Styles]"),
+        "[V4+ Styles] diverged"
+    );
+    assert_eq!(
+        dialogue(&output),
+        dialogue(&input),
+        "[Events] dialogue diverged"
+    );
+}
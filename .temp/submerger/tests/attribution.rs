@@ -0,0 +1,105 @@
+//! `--attribution`: the JSON map from each output cue's number to the input,
+//! or inputs for a stacked cue, it came from. The numbers are the ones the
+//! SRT output carries, so they follow `--no-renumber` and restart in every
+//! chunk.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn attribution(name: &str, inputs: [&str; 2], args: &[&str]) -> String {
+    let output_path = std::env::temp_dir().join(format!("{}.srt", name));
+    let map_path = std::env::temp_dir().join(format!("{}.json", name));
+    let output = submerger()
+        .arg(fixture(inputs[0]))
+        .arg(fixture(inputs[1]))
+        .arg(&output_path)
+        .args(args)
+        .arg("--attribution")
+        .arg(&map_path)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let map = fs::read_to_string(&map_path).unwrap();
+    fs::remove_file(output_path).ok();
+    fs::remove_file(map_path).ok();
+    map
+}
+
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", fixture(name).display())
+}
+
+#[test]
+fn separate_cues_map_to_one_input_each() {
+    let (en, de) = (quoted("en.srt"), quoted("de.srt"));
+    assert_eq!(
+        attribution(
+            "submerger-attribution-sequential",
+            ["en.srt", "de.srt"],
+            &["--merge-strategy", "sequential"]
+        ),
+        format!(
+            "{{\n  \"1\": {en},\n  \"2\": {de},\n  \"3\": {en},\n  \"4\": {de},\n  \"5\": {en},\n  \"6\": {de}\n}}\n"
+        )
+    );
+}
+
+#[test]
+fn stacked_cues_map_to_every_input_in_them() {
+    let (en, de) = (quoted("en.srt"), quoted("de.srt"));
+    assert_eq!(
+        attribution(
+            "submerger-attribution-vertical",
+            ["en.srt", "de.srt"],
+            &["--merge-strategy", "vertical"]
+        ),
+        format!(
+            "{{\n  \"1\": [{en}, {de}],\n  \"2\": {en},\n  \"3\": {de},\n  \"4\": [{en}, {de}]\n}}\n"
+        )
+    );
+}
+
+#[test]
+fn no_renumber_keeps_the_numbers_written() {
+    let (numbered, de) = (quoted("numbered.srt"), quoted("de.srt"));
+    assert_eq!(
+        attribution(
+            "submerger-attribution-no-renumber",
+            ["numbered.srt", "de.srt"],
+            &["--concat", "--no-renumber"]
+        ),
+        format!(
+            "{{\n  \"1\": {numbered},\n  \"2\": {numbered},\n  \"5\": {numbered},\n  \"6\": {de},\n  \"7\": {de},\n  \"8\": {de}\n}}\n"
+        )
+    );
+}
+
+#[test]
+fn chunks_get_a_map_each() {
+    let (en, de) = (quoted("en.srt"), quoted("de.srt"));
+    let chunk = |i: usize| {
+        let path = std::env::temp_dir().join(format!("submerger-attribution-chunked-{}.srt", i));
+        let quoted = format!("\"{}\"", path.display());
+        fs::remove_file(path).ok();
+        quoted
+    };
+    let map = attribution(
+        "submerger-attribution-chunked",
+        ["en.srt", "de.srt"],
+        &["--chunk-duration", "4s"],
+    );
+    let files: Vec<String> = (1..=3)
+        .map(|i| {
+            format!(
+                "  {}: {{\n    \"1\": {en},\n    \"2\": {de}\n  }}",
+                chunk(i)
+            )
+        })
+        .collect();
+    assert_eq!(map, format!("{{\n{}\n}}\n", files.join(",\n")));
+}
@@ -0,0 +1,28 @@
+//! `--bundle-window` at its edges: the first cues of `bundle-a.srt` and
+//! `bundle-b.srt` start 250ms apart and the second ones 350ms apart, so a
+//! 300ms window stacks the first pair and leaves the second pair apart.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+#[test]
+fn stacks_starts_inside_the_window_only() {
+    let output = submerger()
+        .arg(fixture("bundle-a.srt"))
+        .arg(fixture("bundle-b.srt"))
+        .arg("-")
+        .args(["--merge-strategy", "vertical", "--bundle-window", "300ms"])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1\n00:00:01,000 --> 00:00:03,000\nNear.\nNah.\n\n\
+         2\n00:00:05,000 --> 00:00:07,000\nFar.\n\n\
+         3\n00:00:05,350 --> 00:00:07,000\nFern.\n\n"
+    );
+}
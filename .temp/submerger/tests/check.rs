@@ -0,0 +1,38 @@
+//! `--check` validates every input without writing output.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+#[test]
+fn reports_each_input_and_fails_on_bad_ones() {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("notes.txt"))
+        .arg("--add-input")
+        .arg(fixture("colored.ass"))
+        .arg("--add-input")
+        .arg(fixture("broken.srt"))
+        .arg("--check")
+        .run();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert!(!output.status.success());
+    assert_eq!(lines.len(), 4, "{}", stdout);
+    assert!(lines[0].starts_with("OK ") && lines[0].contains("en.srt"));
+    assert!(lines[1].starts_with("FAIL") && lines[1].contains("notes.txt"));
+    assert!(lines[2].starts_with("OK ") && lines[2].contains("colored.ass"));
+    assert!(lines[3].starts_with("FAIL") && lines[3].contains("minutes '61'"));
+}
+
+#[test]
+fn succeeds_when_every_input_is_valid() {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg("--check")
+        .run();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 2);
+}
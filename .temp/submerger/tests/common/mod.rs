@@ -0,0 +1,29 @@
+//! Helpers shared by the integration tests. Each test file is its own
+//! crate and uses only some of them.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A file in `tests/fixtures`.
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// The built binary, ready for arguments.
+pub fn submerger() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_submerger"))
+}
+
+pub trait Run {
+    /// Runs the command to completion, capturing stdout and stderr.
+    fn run(&mut self) -> Output;
+}
+
+impl Run for Command {
+    fn run(&mut self) -> Output {
+        self.output().expect("failed to run submerger")
+    }
+}
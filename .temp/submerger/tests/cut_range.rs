@@ -0,0 +1,55 @@
+//! `--cut-range`: `en.srt` has cues at 1s-3.5s, 4s-6.25s and 7.12s-9s.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview(args: &[&str]) -> String {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "9"])
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn later_cues_move_by_the_cut_length() {
+    let preview = preview(&["--cut-range", "00:00:03,800-00:00:06,500"]);
+    assert!(
+        preview.contains("en.srt': dropped 1 cues inside --cut-range"),
+        "{}",
+        preview
+    );
+    assert!(!preview.contains("Where are you going?"), "{}", preview);
+    assert!(
+        preview.contains("00:00:04,420 --> 00:00:06,300"),
+        "{}",
+        preview
+    );
+    // Cues before the cut stay put.
+    assert!(
+        preview.contains("00:00:01,000 --> 00:00:03,500"),
+        "{}",
+        preview
+    );
+}
+
+#[test]
+fn an_input_number_limits_the_cut() {
+    let preview = preview(&["--cut-range", "00:00:03,800-00:00:06,500@1"]);
+    assert!(
+        preview.contains("00:00:04,420 --> 00:00:06,300"),
+        "{}",
+        preview
+    );
+    assert!(
+        preview.contains("00:00:07,120 --> 00:00:09,000"),
+        "{}",
+        preview
+    );
+    assert!(preview.contains("Wohin gehst du?"), "{}", preview);
+}
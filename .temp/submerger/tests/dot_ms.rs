@@ -0,0 +1,44 @@
+//! SRT with `.` before the milliseconds: `dot-ms.srt` is read like any SRT
+//! and written back with the canonical comma.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use submerger::{parse_file, Cue};
+
+#[test]
+fn parses_dot_separated_times() {
+    let subtitle = parse_file(fixture("dot-ms.srt")).unwrap();
+    assert_eq!(subtitle.format, "srt");
+    assert_eq!(
+        subtitle.cues(),
+        [
+            Cue::new(1500, 3250, "Dots, not commas."),
+            Cue::new(4000, 5125, "Still SRT."),
+        ]
+    );
+}
+
+#[test]
+fn writes_them_back_with_commas() {
+    let output_path = std::env::temp_dir().join("submerger-dot-ms.srt");
+    let output = submerger()
+        .arg(fixture("dot-ms.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--keep-only-sources", "1"])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    assert_eq!(
+        content,
+        "1\n00:00:01,500 --> 00:00:03,250\nDots, not commas.\n\n\
+         2\n00:00:04,000 --> 00:00:05,125\nStill SRT.\n\n"
+    );
+}
@@ -0,0 +1,54 @@
+//! Table-driven checks of the shared duration parser, exercised through
+//! `--concat-gap`: the second part of `en.srt` + `de.srt` starts at 10 s
+//! plus the gap, so every spelling can be read back from the preview.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn concat_with_gap(value: &str) -> Output {
+    submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "4", "--concat"])
+        .arg(format!("--concat-gap={}", value))
+        .run()
+}
+
+#[test]
+fn accepts_every_spelling_including_negatives() {
+    let cases = [
+        ("250ms", "00:00:10,250"),
+        ("-250ms", "00:00:09,750"),
+        ("2.5s", "00:00:12,500"),
+        ("-1.2s", "00:00:08,800"),
+        ("+2s", "00:00:12,000"),
+        ("00:00:02.500", "00:00:12,500"),
+        ("-00:00:01.000", "00:00:09,000"),
+        ("1500", "00:00:11,500"),
+        ("0", "00:00:10,000"),
+    ];
+    for (value, expected) in cases {
+        let output = concat_with_gap(value);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{} rejected: {}", value, stderr);
+        assert!(
+            stderr.contains(&format!("   4  {} -->", expected)),
+            "gap {} did not start the second part at {}: {}",
+            value,
+            expected,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn rejects_malformed_durations() {
+    for value in [
+        "", "s", "ms", "1.2.3s", "--1s", "2 s", "1h", "-1e3ms", "00:61:00",
+    ] {
+        let output = concat_with_gap(value);
+        assert!(!output.status.success(), "{} was accepted", value);
+    }
+}
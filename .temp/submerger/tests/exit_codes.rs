@@ -0,0 +1,62 @@
+//! The documented exit codes: 0 success, 2 parse errors, 3 I/O errors,
+//! 4 empty merges and 5 validation failures.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::path::PathBuf;
+
+fn exit_code(inputs: [PathBuf; 2], args: &[&str]) -> i32 {
+    let output_path = std::env::temp_dir().join("submerger-exit-codes.srt");
+    submerger()
+        .args(inputs)
+        .arg(&output_path)
+        .args(args)
+        .run()
+        .status
+        .code()
+        .expect("submerger was killed by a signal")
+}
+
+#[test]
+fn success_is_zero() {
+    assert_eq!(exit_code([fixture("en.srt"), fixture("de.srt")], &[]), 0);
+}
+
+#[test]
+fn parse_errors_are_two() {
+    assert_eq!(
+        exit_code([fixture("broken.srt"), fixture("de.srt")], &[]),
+        2
+    );
+    assert_eq!(exit_code([fixture("notes.txt"), fixture("de.srt")], &[]), 2);
+}
+
+#[test]
+fn unreadable_inputs_are_three() {
+    assert_eq!(
+        exit_code([fixture("missing.srt"), fixture("de.srt")], &[]),
+        3
+    );
+}
+
+#[test]
+fn empty_merges_are_four() {
+    assert_eq!(
+        exit_code([fixture("en.srt"), fixture("de.srt")], &["--filter", "."]),
+        4
+    );
+}
+
+#[test]
+fn validation_failures_are_five() {
+    let inputs = || [fixture("en.srt"), fixture("de.srt")];
+    assert_eq!(exit_code(inputs(), &["--priority", "1,1"]), 5);
+    assert_eq!(exit_code(inputs(), &["--no-such-flag"]), 5);
+    assert_eq!(exit_code(inputs(), &["--intersect"]), 5);
+    let output = submerger()
+        .args([fixture("en.srt"), fixture("notes.txt")])
+        .arg("--check")
+        .run();
+    assert_eq!(output.status.code(), Some(5));
+}
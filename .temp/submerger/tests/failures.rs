@@ -0,0 +1,45 @@
+//! Inputs that fail to load are collected into one summary unless
+//! `--fail-fast` asks for the first error only.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::path::PathBuf;
+use std::process::Output;
+
+fn run(inputs: [PathBuf; 2], args: &[&str]) -> Output {
+    let output_path = std::env::temp_dir().join("submerger-failures.srt");
+    submerger().args(inputs).arg(&output_path).args(args).run()
+}
+
+#[test]
+fn lists_the_bad_input_next_to_a_good_one() {
+    let output = run([fixture("en.srt"), fixture("missing.srt")], &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("1 of 2 inputs failed:"), "{}", stderr);
+    assert!(stderr.contains("missing.srt"), "{}", stderr);
+    assert!(!stderr.contains("en.srt"), "{}", stderr);
+}
+
+#[test]
+fn lists_every_bad_input() {
+    let output = run([fixture("missing-1.srt"), fixture("missing-2.srt")], &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("2 of 2 inputs failed:"), "{}", stderr);
+    assert!(stderr.contains("missing-1.srt"), "{}", stderr);
+    assert!(stderr.contains("missing-2.srt"), "{}", stderr);
+}
+
+#[test]
+fn fail_fast_stops_at_the_first_bad_input() {
+    let output = run(
+        [fixture("missing-1.srt"), fixture("missing-2.srt")],
+        &["--fail-fast"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("missing-1.srt"), "{}", stderr);
+    assert!(!stderr.contains("missing-2.srt"), "{}", stderr);
+}
@@ -0,0 +1,57 @@
+//! `--fill-gaps`: `en.srt` leaves 500ms between its first two cues and
+//! 870ms between the last two.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(name: &str, args: &[&str]) -> String {
+    let output_path = std::env::temp_dir().join(format!("submerger-fill-gaps-{}.srt", name));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--keep-only-sources", "1", "--fill-gaps"])
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+fn timings(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|line| line.contains(" --> "))
+        .collect()
+}
+
+#[test]
+fn each_cue_lasts_until_the_next() {
+    assert_eq!(
+        timings(&merge("unlimited", &[])),
+        [
+            "00:00:01,000 --> 00:00:04,000",
+            "00:00:04,000 --> 00:00:07,120",
+            "00:00:07,120 --> 00:00:09,000",
+        ]
+    );
+}
+
+#[test]
+fn fill_max_caps_the_extension() {
+    assert_eq!(
+        timings(&merge("capped", &["--fill-max", "600ms"])),
+        [
+            "00:00:01,000 --> 00:00:04,000",
+            "00:00:04,000 --> 00:00:06,850",
+            "00:00:07,120 --> 00:00:09,000",
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+//! `--filter` drops cues whose text matches and `--filter-replace` rewrites
+//! cue text; a bad pattern or pair is a validation failure.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn merge(name: &str, args: &[&str]) -> (Output, Vec<String>) {
+    let output_path = std::env::temp_dir().join(name);
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .arg("--quiet")
+        .args(args)
+        .run();
+    // Each block's lines after its number and timing.
+    let texts = std::fs::read_to_string(&output_path)
+        .unwrap_or_default()
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| block.lines().skip(2).collect::<Vec<_>>().join("\n"))
+        .collect();
+    std::fs::remove_file(output_path).ok();
+    (output, texts)
+}
+
+#[test]
+fn filter_drops_matching_cues() {
+    let (output, texts) = merge(
+        "submerger-filter.srt",
+        &["--filter", "^W", "--filter", "(?i)bahnhof"],
+    );
+    assert!(output.status.success());
+    assert_eq!(texts, ["Good morning.", "Guten Morgen.", "To the station."]);
+}
+
+#[test]
+fn filter_replace_rewrites_each_match() {
+    let (output, texts) = merge(
+        "submerger-filter-replace.srt",
+        &[
+            "--filter-replace",
+            "Morgen=>Tag",
+            "--filter-replace",
+            r"(\w+)\?=>$1!",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        texts,
+        [
+            "Good morning.",
+            "Guten Tag.",
+            "Where are you going!",
+            "Wohin gehst du!",
+            "To the station.",
+            "Zum Bahnhof.",
+        ]
+    );
+}
+
+#[test]
+fn invalid_patterns_are_validation_failures() {
+    for args in [
+        ["--filter", "("],
+        ["--filter-replace", "(=>x"],
+        ["--filter-replace", "no arrow"],
+    ] {
+        let (output, _) = merge("submerger-filter-invalid.srt", &args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(5), "{:?}: {}", args, stderr);
+        assert!(stderr.starts_with("Error: "), "{}", stderr);
+    }
+}
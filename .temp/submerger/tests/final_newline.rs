@@ -0,0 +1,41 @@
+//! `--final-newline`: whether SRT output ends with a blank line after the
+//! last cue (`yes`, the default) or with the last cue's line break (`no`).
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(args: &[&str]) -> String {
+    let output_path =
+        std::env::temp_dir().join(format!("submerger-final-newline{}.srt", args.join("")));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+#[test]
+fn yes_ends_with_a_blank_line() {
+    let content = merge(&["--final-newline", "yes"]);
+    assert!(content.ends_with("\nZum Bahnhof.\n\n"), "{:?}", content);
+    assert!(!content.ends_with("\n\n\n"), "{:?}", content);
+    assert_eq!(content, merge(&[]));
+}
+
+#[test]
+fn no_ends_with_one_line_break() {
+    let content = merge(&["--final-newline", "no"]);
+    assert!(content.ends_with("\nZum Bahnhof.\n"), "{:?}", content);
+    assert!(!content.ends_with("\n\n"), "{:?}", content);
+}
@@ -0,0 +1,45 @@
+//! `--format-in`: `arrow.ass`, copied here under a `.srt` name, has a `-->`
+//! in its text, so both its name and its content suggest SRT.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+/// The merged SRT and stderr.
+fn merge(name: &str, args: &[&str]) -> (String, String) {
+    let input_path = std::env::temp_dir().join(format!("{}-input.srt", name));
+    fs::copy(fixture("arrow.ass"), &input_path).unwrap();
+    let output_path = std::env::temp_dir().join(format!("{}.srt", name));
+    let output = submerger()
+        .arg(&input_path)
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(input_path).ok();
+    fs::remove_file(output_path).ok();
+    (content, stderr)
+}
+
+#[test]
+fn detection_reads_the_ass_input_as_srt() {
+    let (content, stderr) = merge("submerger-format-detected", &[]);
+    assert!(stderr.contains("-input.srt: 0, "), "{}", stderr);
+    assert!(!content.contains("Platform 2"), "{}", content);
+}
+
+#[test]
+fn format_per_input_overrides_detection() {
+    let (content, stderr) = merge("submerger-format-in", &["--format-in", "ass,srt"]);
+    assert!(stderr.contains("-input.srt: 2, "), "{}", stderr);
+    assert!(
+        content.starts_with("1\n00:00:01,000 --> 00:00:02,000\nPlatform 2 --> this way\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("\nGuten Morgen.\n"), "{}", content);
+}
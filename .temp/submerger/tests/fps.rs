@@ -0,0 +1,57 @@
+//! Frame-based windows: `2f` at `--fps 23.976` is 2000 / 23.976 = 83.4ms,
+//! rounded to 83ms. Frames need a frame rate that is finite and above zero.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn report(args: &[&str]) -> Output {
+    submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "0", "--merge-strategy", "vertical"])
+        .args(["--bundle-window", "2f", "--merge-window-report"])
+        .args(args)
+        .run()
+}
+
+#[test]
+fn frames_convert_to_rounded_milliseconds() {
+    let output = report(&["--fps", "23.976"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "cue 2  00:00:04,000 --> 00:00:06,250: not grouped; the next cue starts 100ms later, window 83ms"
+        ),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn fps_must_be_finite_and_positive() {
+    for fps in ["0", "-25", "NaN", "inf"] {
+        let output = report(&[&format!("--fps={}", fps)]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(5), "{}: {}", fps, stderr);
+        assert!(
+            stderr.contains("must be a finite number greater than 0"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn frames_without_fps_are_refused() {
+    let output = report(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(5), "{}", stderr);
+    assert!(
+        stderr.contains("frame-based values like '2f' require --fps"),
+        "{}",
+        stderr
+    );
+}
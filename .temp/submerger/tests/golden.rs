@@ -0,0 +1,352 @@
+//! Golden-file tests for the serializers. Each case merges fixed fixtures
+//! with fixed options and compares the result byte-for-byte against the
+//! expected file in `tests/golden`. Run with `UPDATE_SNAPSHOTS=1` to
+//! regenerate the expected files after an intentional format change.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use std::path::Path;
+
+fn run(inputs: &[&str], output_path: &Path, args: &[&str]) {
+    let status = submerger()
+        .args(inputs.iter().map(|input| fixture(input)))
+        .arg(output_path)
+        .args(args)
+        .run();
+    assert!(
+        status.status.success(),
+        "submerger failed for {}: {}",
+        output_path.display(),
+        String::from_utf8_lossy(&status.stderr)
+    );
+}
+
+fn compare_golden(name: &str, actual_path: &Path) {
+    let expected_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+
+    let actual = fs::read(actual_path).unwrap();
+    fs::remove_file(actual_path).ok();
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&expected_path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read(&expected_path)
+        .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_SNAPSHOTS=1", name));
+    assert!(
+        actual == expected,
+        "{} drifted from its golden file\n--- expected\n{}\n--- actual\n{}",
+        name,
+        String::from_utf8_lossy(&expected),
+        String::from_utf8_lossy(&actual)
+    );
+}
+
+fn check_golden(name: &str, inputs: &[&str], args: &[&str]) {
+    let output_path = std::env::temp_dir().join(format!("submerger-golden-{}", name));
+    run(inputs, &output_path, args);
+    compare_golden(name, &output_path);
+}
+
+#[test]
+fn srt_sequential() {
+    check_golden("sequential.srt", &["en.srt", "colored.ass"], &[]);
+}
+
+#[test]
+fn srt_bilingual_stacked() {
+    check_golden(
+        "stacked.srt",
+        &["en.srt", "de.srt"],
+        &["--merge-strategy", "vertical", "--bundle-window", "200"],
+    );
+}
+
+#[test]
+fn ass_colored() {
+    check_golden("colored.ass", &["colored.ass", "en.srt"], &[]);
+}
+
+#[test]
+fn ass_bilingual_stacked() {
+    check_golden(
+        "stacked.ass",
+        &["colored.ass", "de.srt"],
+        &["--merge-strategy", "vertical", "--bundle-window", "200"],
+    );
+}
+
+#[test]
+fn ass_stacked_tags_stay_per_line() {
+    check_golden(
+        "stacked-tags.ass",
+        &["italic.ass", "colored.ass"],
+        &["--merge-strategy", "vertical"],
+    );
+}
+
+#[test]
+fn srt_stacked_html_tags_closed_per_line() {
+    check_golden(
+        "stacked-tags.srt",
+        &["unclosed.srt", "de.srt"],
+        &["--merge-strategy", "vertical", "--bundle-window", "200"],
+    );
+}
+
+#[test]
+fn vtt_without_hours() {
+    check_golden(
+        "short-times.vtt",
+        &["en.vtt", "de.srt"],
+        &["--merge-strategy", "vertical", "--vtt-hours", "auto"],
+    );
+}
+
+#[test]
+fn srt_animation_tags_made_static() {
+    check_golden("animated.srt", &["animated.ass", "de.srt"], &[]);
+}
+
+#[test]
+fn ass_animation_tags_kept() {
+    check_golden("animated.ass", &["animated.ass", "de.srt"], &[]);
+}
+
+#[test]
+fn vtt_top_positioned() {
+    check_golden("positioned.vtt", &["positioned.ass", "en.srt"], &[]);
+}
+
+#[test]
+fn srt_chunked_by_cue_count() {
+    let dir = std::env::temp_dir();
+    run(
+        &["en.srt", "de.srt"],
+        &dir.join("submerger-golden-chunked.srt"),
+        &["--chunk-cues", "4"],
+    );
+    compare_golden("chunked-1.srt", &dir.join("submerger-golden-chunked-1.srt"));
+    compare_golden("chunked-2.srt", &dir.join("submerger-golden-chunked-2.srt"));
+    assert!(!dir.join("submerger-golden-chunked-3.srt").exists());
+}
+
+#[test]
+fn srt_intersect_drops_single_source_cues() {
+    check_golden(
+        "intersect.srt",
+        &["en.srt", "de.srt"],
+        &["--merge-strategy", "vertical", "--intersect"],
+    );
+}
+
+#[test]
+fn srt_from_reordered_ass_fields() {
+    check_golden("reordered.srt", &["reordered.ass", "de.srt"], &[]);
+}
+
+#[test]
+fn srt_same_input_twice_deduplicated() {
+    check_golden("dedup.srt", &["en.srt", "en.srt"], &["--dedup-inputs"]);
+}
+
+#[test]
+fn srt_silence_marker_at_large_gap() {
+    check_golden(
+        "silence.srt",
+        &["en.srt", "de.srt"],
+        &[
+            "--concat",
+            "--concat-gap",
+            "10s",
+            "--silence-markers",
+            "5s",
+            "--silence-text",
+            "(pause)",
+        ],
+    );
+}
+
+#[test]
+fn ass_interior_blank_lines_trimmed() {
+    check_golden(
+        "blank-lines.ass",
+        &["blank-lines.ass", "de.srt"],
+        &["--trim-whitespace-lines"],
+    );
+}
+
+#[test]
+fn ass_three_tracks_at_fixed_positions() {
+    let third = fixture("de.srt");
+    check_golden(
+        "three-tracks.ass",
+        &["colored.ass", "en.srt"],
+        &[
+            "--add-input",
+            third.to_str().unwrap(),
+            "--merge-strategy",
+            "vertical",
+            "--bundle-window",
+            "200",
+            "--positions",
+            "an8,an5,an2",
+        ],
+    );
+}
+
+#[test]
+fn srt_rebased_to_lead_in() {
+    check_golden(
+        "rebased.srt",
+        &["en.srt", "de.srt"],
+        &["--shift-first-cue-to", "01:23:45,000", "--rebase", "250ms"],
+    );
+}
+
+#[test]
+fn srt_html_entities_decoded() {
+    check_golden(
+        "entities.srt",
+        &["entities.srt", "de.srt"],
+        &["--decode-entities"],
+    );
+}
+
+#[test]
+fn srt_four_line_stack_split_in_two() {
+    check_golden(
+        "max-lines.srt",
+        &["two-line-en.srt", "two-line-de.srt"],
+        &["--merge-strategy", "vertical", "--max-lines", "2"],
+    );
+}
+
+#[test]
+fn srt_overwrite_later_input_wins() {
+    check_golden(
+        "overwrite.srt",
+        &["en.srt", "de.srt"],
+        &["--merge-strategy", "overwrite"],
+    );
+}
+
+#[test]
+fn srt_overwrite_priority_reorders_winner() {
+    check_golden(
+        "overwrite-priority.srt",
+        &["en.srt", "de.srt"],
+        &["--merge-strategy", "overwrite", "--priority", "1,2"],
+    );
+}
+
+#[test]
+fn srt_ass_timer_speed_scales_times() {
+    check_golden("timer.srt", &["timer.ass", "en.srt"], &[]);
+}
+
+#[test]
+fn srt_boundaries_snapped_to_shot_changes() {
+    let shots = fixture("shots.txt");
+    check_golden(
+        "snapped.srt",
+        &["en.srt", "de.srt"],
+        &["--snap-to", shots.to_str().unwrap()],
+    );
+}
+
+#[test]
+fn srt_stacked_cue_flattened_to_one_line() {
+    check_golden(
+        "one-line.srt",
+        &["en.srt", "de.srt"],
+        &[
+            "--merge-strategy",
+            "vertical",
+            "--one-line",
+            "--one-line-separator",
+            " | ",
+        ],
+    );
+}
+
+#[test]
+fn srt_swapped_times_fixed() {
+    check_golden(
+        "swapped.srt",
+        &["swapped.srt", "de.srt"],
+        &["--fix-swapped"],
+    );
+}
+
+#[test]
+fn srt_repeated_cues_collapsed() {
+    check_golden(
+        "collapsed.srt",
+        &["stutter.srt", "de.srt"],
+        &["--collapse-consecutive"],
+    );
+}
+
+#[test]
+fn srt_concat_keeps_part_numbers() {
+    check_golden(
+        "no-renumber.srt",
+        &["numbered.srt", "de.srt"],
+        &["--concat", "--no-renumber"],
+    );
+}
+
+#[test]
+fn ass_rounding_keeps_close_cues_apart() {
+    check_golden("rounding.ass", &["de.srt", "close.srt"], &[]);
+}
+
+#[test]
+fn srt_diff_friendly_layout() {
+    check_golden(
+        "diff-friendly.srt",
+        &["untidy.srt", "de.srt"],
+        &["--diff-friendly"],
+    );
+}
+
+#[test]
+fn vtt_diff_friendly_layout() {
+    check_golden(
+        "diff-friendly.vtt",
+        &["untidy.srt", "de.srt"],
+        &["--diff-friendly"],
+    );
+}
+
+#[test]
+fn srt_from_mixed_inputs() {
+    check_golden(
+        "mixed.srt",
+        &["unclosed.srt", "complex.ass"],
+        &["--merge-strategy", "vertical"],
+    );
+}
+
+#[test]
+fn ass_from_mixed_inputs_by_format_out() {
+    // The extension says nothing; --format-out picks ASS.
+    let output_path = std::env::temp_dir().join("submerger-golden-mixed.txt");
+    run(
+        &["unclosed.srt", "complex.ass"],
+        &output_path,
+        &["--merge-strategy", "vertical", "--format-out", "ass"],
+    );
+    compare_golden("mixed.ass", &output_path);
+}
+
+#[test]
+fn srt_index_width_pads_numbers() {
+    check_golden("padded.srt", &["en.srt", "de.srt"], &["--index-width", "4"]);
+}
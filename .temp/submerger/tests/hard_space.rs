@@ -0,0 +1,55 @@
+//! ASS `\h` hard spaces in SRT and WebVTT output: `hard-space.ass` has
+//! `Dr.\hWatson, 100\hkm` and a cue with a no-break space written as is.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(extension: &str, args: &[&str]) -> String {
+    let output_path = std::env::temp_dir().join(format!(
+        "submerger-hard-space{}.{}",
+        args.join(""),
+        extension
+    ));
+    let output = submerger()
+        .arg(fixture("hard-space.ass"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+#[test]
+fn hard_spaces_become_plain_spaces_in_srt() {
+    let content = merge("srt", &[]);
+    assert!(content.contains("\nDr. Watson, 100 km\n"), "{}", content);
+    assert!(content.contains("\nWait\u{a0}here.\n"), "{}", content);
+}
+
+#[test]
+fn hard_spaces_can_stay_unbreakable() {
+    let content = merge("srt", &["--hard-space", "nbsp"]);
+    assert!(
+        content.contains("\nDr.\u{a0}Watson, 100\u{a0}km\n"),
+        "{}",
+        content
+    );
+    let content = merge("vtt", &["--hard-space", "nbsp"]);
+    assert!(content.contains("\nDr.\u{a0}Watson"), "{}", content);
+}
+
+#[test]
+fn collapse_nbsp_makes_stray_ones_plain() {
+    let content = merge("srt", &["--collapse-nbsp", "--hard-space", "nbsp"]);
+    assert!(content.contains("\nWait here.\n"), "{}", content);
+    assert!(content.contains("\nDr.\u{a0}Watson"), "{}", content);
+}
@@ -0,0 +1,101 @@
+//! `--header-comment`: each format gets the provenance header as its own
+//! kind of comment, and the output still parses to the same cues.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge_with_header(extension: &str) -> (String, std::path::PathBuf) {
+    let output_path = std::env::temp_dir().join(format!("submerger-header.{}", extension));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--header-comment", "--merge-strategy", "vertical"])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (fs::read_to_string(&output_path).unwrap(), output_path)
+}
+
+fn assert_header(content: &str, prefix: &str) {
+    let version = format!(
+        "{}Merged by submerger {} on ",
+        prefix,
+        env!("CARGO_PKG_VERSION")
+    );
+    assert!(content.contains(&version), "{}", content);
+    assert!(
+        content.contains(&format!("{}Inputs: ", prefix)),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains(&format!(
+            "{}Options: --header-comment --merge-strategy vertical",
+            prefix
+        )),
+        "{}",
+        content
+    );
+}
+
+#[test]
+fn srt_header_is_skipped_when_parsed() {
+    let (content, path) = merge_with_header("srt");
+    assert!(content.starts_with("Merged by submerger"), "{}", content);
+    assert_header(&content, "");
+    assert_eq!(submerger::parse_file(&path).unwrap().dialogs.len(), 4);
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn ass_header_is_in_script_info() {
+    let (content, path) = merge_with_header("ass");
+    assert!(
+        content.starts_with("[Script Info]\n; Merged by"),
+        "{}",
+        content
+    );
+    assert_header(&content, "; ");
+    assert_eq!(submerger::parse_file(&path).unwrap().dialogs.len(), 4);
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn vtt_header_is_a_note() {
+    let (content, path) = merge_with_header("vtt");
+    assert!(content.starts_with("WEBVTT\n\nNOTE\n"), "{}", content);
+    assert_header(&content, "");
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn options_come_from_the_parsed_arguments() {
+    let output_path = std::env::temp_dir().join("submerger-header-options.srt");
+    let output = submerger()
+        .arg("--merge-strategy=vertical")
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--header-comment", "-q", "--filter=Morgen", "--fps", "25"])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    assert!(
+        content.contains(
+            "\nOptions: --merge-strategy vertical --header-comment --quiet --filter Morgen --fps 25\n"
+        ),
+        "{}",
+        content
+    );
+    fs::remove_file(output_path).ok();
+}
@@ -0,0 +1,46 @@
+//! `--ignore-format-errors`: `arrow.ass` has a `-->` in its text, so it is
+//! detected as SRT, which finds no cues in it.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+/// The merged SRT and stderr.
+fn merge(name: &str, args: &[&str]) -> (String, String) {
+    let output_path = std::env::temp_dir().join(name);
+    let output = submerger()
+        .arg(fixture("arrow.ass"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    (content, stderr)
+}
+
+#[test]
+fn misdetected_input_contributes_nothing_by_default() {
+    let (content, stderr) = merge("submerger-misdetected.srt", &[]);
+    assert!(!content.contains("Platform 2"), "{}", content);
+    assert!(stderr.contains("arrow.ass: 0, "), "{}", stderr);
+}
+
+#[test]
+fn other_parser_salvages_the_input() {
+    let (content, stderr) = merge("submerger-salvaged.srt", &["--ignore-format-errors"]);
+    assert!(
+        stderr.contains("arrow.ass' parsed with the ass parser after the srt parser failed"),
+        "{}",
+        stderr
+    );
+    assert!(
+        content.starts_with("1\n00:00:01,000 --> 00:00:02,000\nPlatform 2 --> this way\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("\nWhere are you going?\n"), "{}", content);
+}
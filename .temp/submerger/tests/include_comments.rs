@@ -0,0 +1,49 @@
+//! `--include-comments`: `comments.ass` has a `Comment:` event between two
+//! `Dialogue:` lines.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(extension: &str, args: &[&str]) -> String {
+    let output_path =
+        std::env::temp_dir().join(format!("submerger-comments{}.{}", args.join(""), extension));
+    let output = submerger()
+        .arg(fixture("comments.ass"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+const COMMENT: &str = "Comment: 0,0:00:02.50,0:00:03.50,Default,,0,0,0,,TL note: a greeting\n";
+
+#[test]
+fn comments_are_dropped_by_default() {
+    let content = merge("ass", &[]);
+    assert!(!content.contains("TL note"), "{}", content);
+    assert!(content.contains(",,Good morning.\n"), "{}", content);
+}
+
+#[test]
+fn included_comments_stay_comments_in_ass() {
+    let content = merge("ass", &["--include-comments"]);
+    assert!(content.contains(COMMENT), "{}", content);
+    assert!(!content.contains("Dialogue: 0,0:00:02.50"), "{}", content);
+}
+
+#[test]
+fn included_comments_are_skipped_in_srt() {
+    let content = merge("srt", &["--include-comments"]);
+    assert!(!content.contains("TL note"), "{}", content);
+    assert!(content.contains("\nWhere are you going?\n"), "{}", content);
+}
@@ -0,0 +1,43 @@
+//! `--join-cjk-lines`: `cjk.srt` has two-line Japanese cues, one with its
+//! first line in italics, a two-line English cue and a Japanese exchange
+//! between two speakers.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview_texts(args: &[&str]) -> Vec<String> {
+    let output = submerger()
+        .arg(fixture("cjk.srt"))
+        .arg(fixture("cjk.srt"))
+        .args(["--preview", "9", "--keep-only-sources", "1"])
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+        .lines()
+        .filter(|line| !line.contains("-->"))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn cjk_lines_join_without_a_space() {
+    let texts = preview_texts(&["--join-cjk-lines"]);
+    assert_eq!(texts[0], "駅まで行きましょう。");
+    assert_eq!(texts[2], "<i>今日は</i>いい天気ですね。");
+}
+
+#[test]
+fn latin_lines_join_with_a_space() {
+    let texts = preview_texts(&["--join-cjk-lines"]);
+    assert_eq!(texts[1], "Let's go to the station.");
+}
+
+#[test]
+fn speakers_stay_on_their_own_lines() {
+    let texts = preview_texts(&["--join-cjk-lines"]);
+    assert_eq!(texts[3..], ["- 本当？", "- うん。"]);
+    assert_eq!(preview_texts(&[])[..2], ["駅まで", "行きましょう。"]);
+}
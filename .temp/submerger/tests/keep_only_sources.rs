@@ -0,0 +1,42 @@
+//! `--keep-only-sources`: of three inputs, only those selected by file name
+//! or number contribute cues.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn preview(selectors: &str) -> Output {
+    submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg("--add-input")
+        .arg(fixture("ru.srt"))
+        .args(["--preview", "1", "--merge-strategy", "vertical"])
+        .args(["--keep-only-sources", selectors])
+        .run()
+}
+
+fn texts(output: &Output) -> Vec<String> {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+        .lines()
+        .filter(|line| !line.contains("-->"))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn selects_by_name_and_number() {
+    assert_eq!(texts(&preview("ru,1")), ["Good morning.", "Доброе утро."]);
+    assert_eq!(texts(&preview("de.srt")), ["Guten Morgen."]);
+}
+
+#[test]
+fn matching_nothing_is_a_validation_failure() {
+    let output = preview("jp");
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("--keep-only-sources matched none of the 3 inputs"));
+}
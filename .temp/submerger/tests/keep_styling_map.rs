@@ -0,0 +1,57 @@
+//! `--keep-styling-map`: `styled.ass` has an italic `Thoughts` style and a
+//! bold `Sign` style; their events are wrapped in `<i>` and `<b>` in SRT.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(args: &[&str]) -> String {
+    let output_path =
+        std::env::temp_dir().join(format!("submerger-styling-map{}.srt", args.join("")));
+    let output = submerger()
+        .arg(fixture("styled.ass"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--merge-strategy", "vertical"])
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+#[test]
+fn styles_become_html_tags() {
+    let content = merge(&["--keep-styling-map"]);
+    assert!(
+        content.contains("\n<i>Where is everyone going?</i>\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("\n<b>STATION</b>\nZum Bahnhof.\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("\nGood morning.\nGuten Morgen.\n"),
+        "{}",
+        content
+    );
+}
+
+#[test]
+fn styles_are_dropped_by_default() {
+    let content = merge(&[]);
+    assert!(
+        !content.contains("<i>") && !content.contains("<b>"),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,54 @@
+//! `--dedup --keep-variant`: `ocr-a.srt` and `ocr-b.srt` are two OCR runs
+//! of the same captions, differing only in casing, punctuation and spacing.
+//! The last caption repeats later without overlapping and must stay twice.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview(keep: &str) -> String {
+    let output = submerger()
+        .arg(fixture("ocr-a.srt"))
+        .arg(fixture("ocr-b.srt"))
+        .args(["--preview", "9", "--dedup", "--keep-variant", keep])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+fn texts(preview: &str) -> Vec<&str> {
+    preview
+        .lines()
+        .filter(|line| !line.contains("-->"))
+        .map(str::trim)
+        .collect()
+}
+
+#[test]
+fn each_variant_keeps_its_text() {
+    let cases = [
+        ("first", ["Good morning", "Well, okay."]),
+        ("longest", ["good morning!", "Well,    okay"]),
+        ("most-punct", ["good morning!", "Well, okay."]),
+    ];
+    for (keep, [morning, okay]) in cases {
+        let preview = preview(keep);
+        assert_eq!(
+            texts(&preview),
+            [morning, okay, "To the station.", "To the station."],
+            "--keep-variant {}",
+            keep
+        );
+    }
+}
+
+#[test]
+fn collapsed_cues_span_both_originals() {
+    let preview = preview("first");
+    assert!(
+        preview.contains("   2  00:00:04,000 --> 00:00:06,300"),
+        "{}",
+        preview
+    );
+}
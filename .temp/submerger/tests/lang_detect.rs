@@ -0,0 +1,29 @@
+//! `--lang-detect` labels tracks for `{langs}`; only built with the `lang`
+//! feature.
+#![cfg(feature = "lang")]
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+#[test]
+fn labels_english_and_japanese_tracks() {
+    let dir = std::env::temp_dir().join("submerger-lang-detect");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    let output = submerger()
+        .current_dir(&dir)
+        .arg(fixture("en.srt"))
+        .arg(fixture("colored.ass"))
+        .args(["--lang-detect", "--output-template", "{langs}.srt"])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dir.join("eng-jpn.srt").exists());
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,55 @@
+//! `--layer-per-source`: in ASS output each input's events sit on a layer
+//! of their own, numbered from 0 in input order.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+/// The layer and text of each `Dialogue:` line.
+fn layers(args: &[&str]) -> Vec<(String, String)> {
+    let output_path = std::env::temp_dir().join(format!("submerger-layers{}.ass", args.join("")));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("Dialogue: "))
+        .map(|fields| {
+            let fields: Vec<&str> = fields.splitn(10, ',').collect();
+            (fields[0].to_string(), fields[9].to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn each_input_gets_its_own_layer() {
+    let layers = layers(&["--layer-per-source"]);
+    let expected = [
+        ("0", "Good morning."),
+        ("1", "Guten Morgen."),
+        ("0", "Where are you going?"),
+        ("1", "Wohin gehst du?"),
+        ("0", "To the station."),
+        ("1", "Zum Bahnhof."),
+    ]
+    .map(|(layer, text)| (layer.to_string(), text.to_string()));
+    assert_eq!(layers, expected);
+}
+
+#[test]
+fn every_event_is_on_layer_zero_by_default() {
+    let layers = layers(&[]);
+    assert_eq!(layers.len(), 6);
+    assert!(layers.iter().all(|(layer, _)| layer == "0"), "{:?}", layers);
+}
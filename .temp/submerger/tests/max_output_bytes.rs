@@ -0,0 +1,45 @@
+//! `--max-output-bytes`: the sequential merge of `en.srt` and `de.srt` is
+//! 292 bytes, so a smaller limit must abort before anything is written.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use std::process::Output;
+
+fn merge_with_limit(name: &str, limit: &str) -> (Output, std::path::PathBuf) {
+    let output_path = std::env::temp_dir().join(format!("submerger-limit-{}.srt", name));
+    fs::remove_file(&output_path).ok();
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--max-output-bytes", limit])
+        .run();
+    (output, output_path)
+}
+
+#[test]
+fn aborts_over_the_limit_without_writing() {
+    let (output, path) = merge_with_limit("over", "291");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(5), "{}", stderr);
+    assert!(
+        stderr.contains("would be 292 bytes, over the --max-output-bytes limit of 291"),
+        "{}",
+        stderr
+    );
+    assert!(!path.exists());
+}
+
+#[test]
+fn writes_up_to_the_limit() {
+    let (output, path) = merge_with_limit("at", "292");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::metadata(&path).unwrap().len(), 292);
+    fs::remove_file(path).ok();
+}
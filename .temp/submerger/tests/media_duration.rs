@@ -0,0 +1,62 @@
+//! `--media-duration`: the last cues of `en.srt` and `de.srt` run from
+//! 00:00:07,120 to 00:00:09,000, past media cut shorter than that.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+/// The merged SRT and the stderr of the run.
+fn merge(args: &[&str]) -> (String, String) {
+    let output_path = std::env::temp_dir().join(format!(
+        "submerger-media-duration-{}.srt",
+        args.join("").replace(':', "")
+    ));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    (content, stderr)
+}
+
+#[test]
+fn warns_about_cues_past_the_media() {
+    let (content, stderr) = merge(&["--media-duration", "00:00:08,000"]);
+    assert!(
+        stderr.contains(
+            "Warning: 2 cues end after the media's 00:00:08,000, the last at 00:00:09,000"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        content.contains("00:00:07,120 --> 00:00:09,000"),
+        "{}",
+        content
+    );
+
+    let (_, stderr) = merge(&["--media-duration", "00:00:09,000"]);
+    assert!(!stderr.contains("Warning"), "{}", stderr);
+}
+
+#[test]
+fn clamps_and_drops_with_clamp_to_media() {
+    let (content, stderr) = merge(&["--media-duration", "00:00:08,000", "--clamp-to-media"]);
+    assert!(stderr.contains("clamped to the media"), "{}", stderr);
+    assert_eq!(content.matches("00:00:07,120 --> 00:00:08,000").count(), 2);
+    assert!(!content.contains("00:00:09,000"), "{}", content);
+
+    let (content, _) = merge(&["--media-duration", "00:00:07,000", "--clamp-to-media"]);
+    assert!(!content.contains("00:00:07,120"), "{}", content);
+    assert!(
+        content.contains("00:00:04,100 --> 00:00:06,300"),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,72 @@
+//! Merging through the library's [`SubtitleMerger`], without the binary.
+
+mod common;
+
+use common::fixture;
+use std::fs;
+use submerger::{InputOptions, MergeError, MergeStrategy, SourceInfo, SubtitleMerger};
+
+#[test]
+fn builder_merges_inputs_into_the_output_file() {
+    let output = std::env::temp_dir().join("submerger-library-merge.srt");
+    let mut merger = SubtitleMerger::builder()
+        .output(&output)
+        .strategy(MergeStrategy::Vertical)
+        .quiet(true)
+        .build();
+    let offset = InputOptions {
+        offset: 500,
+        ..InputOptions::default()
+    };
+    merger
+        .add_all(&[
+            (fixture("en.srt"), InputOptions::default()),
+            (fixture("de.srt"), offset),
+        ])
+        .unwrap();
+
+    let summary = merger.merge().unwrap();
+    let merged = fs::read_to_string(&output).unwrap();
+    fs::remove_file(&output).ok();
+    assert_eq!(summary.total, 6);
+    assert_eq!((summary.first_start, summary.last_end), (1000, 9500));
+    assert!(merged.starts_with(
+        "1\n00:00:01,000 --> 00:00:03,500\nGood morning.\n\n\
+         2\n00:00:01,500 --> 00:00:03,900\nGuten Morgen.\n\n"
+    ));
+}
+
+#[test]
+fn sources_describe_each_loaded_input_in_order() {
+    let mut merger = SubtitleMerger::builder().quiet(true).build();
+    merger
+        .add_all(&[
+            (fixture("en.srt"), InputOptions::default()),
+            (fixture("en.vtt"), InputOptions::default()),
+        ])
+        .unwrap();
+
+    let sources: Vec<SourceInfo> = merger.sources();
+    let described: Vec<(&str, usize)> = sources
+        .iter()
+        .map(|source| (source.format.as_str(), source.cue_count))
+        .collect();
+    assert_eq!(described, [("srt", 3), ("vtt", 3)]);
+    assert_eq!(sources[0].path, fixture("en.srt").to_string_lossy());
+    assert_eq!(sources[0].encoding, "UTF-8");
+    assert_eq!(
+        sources[1].to_string(),
+        format!("'{}': vtt, UTF-8, 3 cues", fixture("en.vtt").display())
+    );
+}
+
+#[test]
+fn merging_no_inputs_fails_as_empty() {
+    let output = std::env::temp_dir().join("submerger-library-empty.srt");
+    let merger = SubtitleMerger::builder()
+        .output(&output)
+        .quiet(true)
+        .build();
+    assert!(matches!(merger.merge(), Err(MergeError::Empty)));
+    assert!(!output.exists());
+}
@@ -0,0 +1,38 @@
+//! `--min-chars`: `noisy.srt` has a lone note symbol, an italic "a" padded
+//! with spaces, a positioned "Oh" and a full line.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview(min_chars: &str) -> String {
+    let output = submerger()
+        .arg(fixture("noisy.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "9", "--min-chars", min_chars])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn drops_one_character_cues_and_keeps_two() {
+    let preview = preview("2");
+    assert!(
+        preview.contains("noisy.srt': dropped 2 cues shorter than 2 characters"),
+        "{}",
+        preview
+    );
+    assert!(!preview.contains('♪'), "{}", preview);
+    assert!(!preview.contains("00:00:02,500"), "{}", preview);
+    assert!(preview.contains("{\\an8}Oh"), "{}", preview);
+    assert!(preview.contains("Where are you going?"), "{}", preview);
+}
+
+#[test]
+fn counts_nothing_below_the_minimum() {
+    let preview = preview("1");
+    assert!(!preview.contains("dropped"), "{}", preview);
+    assert!(preview.contains('♪'), "{}", preview);
+}
@@ -0,0 +1,39 @@
+//! `--fix-mojibake`: the first two cues of `mojibake.srt` are UTF-8 read
+//! as Windows-1252; the third is a correct "naïve" that must stay as is.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview(args: &[&str]) -> String {
+    let output = submerger()
+        .arg(fixture("mojibake.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "9"])
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn repairs_the_accents() {
+    let preview = preview(&["--fix-mojibake"]);
+    assert!(
+        preview.contains("mojibake.srt': repaired mojibake in 2 cues"),
+        "{}",
+        preview
+    );
+    assert!(preview.contains("Café au lait"), "{}", preview);
+    assert!(preview.contains("À bientôt, Zoë"), "{}", preview);
+    assert!(preview.contains("naïve"), "{}", preview);
+    assert!(!preview.contains('Ã'), "{}", preview);
+}
+
+#[test]
+fn leaves_text_alone_without_the_flag() {
+    let preview = preview(&[]);
+    assert!(preview.contains("CafÃ© au lait"), "{}", preview);
+    assert!(!preview.contains("repaired"), "{}", preview);
+}
@@ -0,0 +1,47 @@
+//! `--ms-sep`: SRT output with either millisecond separator, a comma being
+//! what SRT writes without the option.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(name: &str, args: &[&str]) -> String {
+    let output_path = std::env::temp_dir().join(format!("submerger-ms-sep-{}.srt", name));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+#[test]
+fn comma_is_the_srt_default() {
+    let content = merge("comma", &["--ms-sep", "comma"]);
+    assert!(
+        content.contains("00:00:01,000 --> 00:00:03,500\n"),
+        "{}",
+        content
+    );
+    assert_eq!(content, merge("default", &[]));
+}
+
+#[test]
+fn dot_replaces_the_comma() {
+    let content = merge("dot", &["--ms-sep", "dot"]);
+    assert!(
+        content.contains("00:00:01.000 --> 00:00:03.500\n"),
+        "{}",
+        content
+    );
+    assert!(!content.contains(','), "{}", content);
+}
@@ -0,0 +1,61 @@
+//! Cues shifted before 00:00:00 start there instead, or are dropped if they
+//! also end before it, and the counts are reported.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use submerger::{parse_file, Cue, InputOptions, SubtitleMerger};
+
+#[test]
+fn an_offset_past_zero_clamps_and_drops() {
+    // Moves `de.srt` 6.12s earlier: its first cue ends before zero and its
+    // second starts before it.
+    let output_path = std::env::temp_dir().join("submerger-negative-times.srt");
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--anchor", "00:00:01,000=00:00:07,120"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "de.srt': clamped 1 cues to start at 00:00:00, dropped 1 cues ending before it"
+        ),
+        "{}",
+        stderr
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    assert!(
+        content.starts_with("1\n00:00:00,000 --> 00:00:00,180\nWohin gehst du?\n\n"),
+        "{}",
+        content
+    );
+    assert!(!content.contains("Guten Morgen."), "{}", content);
+}
+
+#[test]
+fn a_negative_input_offset_clamps_in_the_library() {
+    let output_path = std::env::temp_dir().join("submerger-negative-offset.srt");
+    let mut merger = SubtitleMerger::builder()
+        .output(&output_path)
+        .quiet(true)
+        .build();
+    let offset = InputOptions {
+        offset: -2000,
+        ..InputOptions::default()
+    };
+    merger
+        .add_all(&[
+            (fixture("en.srt"), offset),
+            (fixture("de.srt"), InputOptions::default()),
+        ])
+        .unwrap();
+    merger.merge().unwrap();
+    let cues = parse_file(&output_path).unwrap().cues();
+    fs::remove_file(output_path).ok();
+    assert_eq!(cues[0], Cue::new(0, 1500, "Good morning."));
+}
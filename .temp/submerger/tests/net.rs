@@ -0,0 +1,74 @@
+#![cfg(feature = "net")]
+//! `http://` inputs, fetched from a one-request server on a local port.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// Answers the first request on a free port with `status` and `body`, and
+/// returns the URL of `/name`.
+fn serve_once(name: &str, status: &'static str, body: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/{}", listener.local_addr().unwrap(), name);
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+            line.clear();
+        }
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(&body).unwrap();
+    });
+    url
+}
+
+#[test]
+fn merges_a_fetched_input_with_a_local_one() {
+    let url = serve_once("de.srt", "200 OK", fs::read(fixture("de.srt")).unwrap());
+    let output_path = std::env::temp_dir().join("submerger-net.srt");
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(&url)
+        .arg(&output_path)
+        .args(["--merge-strategy", "vertical", "--timeout", "5"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains(&format!("{}: 3", url)), "{}", stderr);
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    assert!(
+        content.contains("\nGood morning.\nGuten Morgen.\n"),
+        "{}",
+        content
+    );
+}
+
+#[test]
+fn a_non_2xx_answer_fails_to_read_the_input() {
+    let url = serve_once("missing.srt", "404 Not Found", b"no such file".to_vec());
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(&url)
+        .arg(std::env::temp_dir().join("submerger-net-404.srt"))
+        .args(["--timeout", "5"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr);
+    assert!(
+        stderr.contains(&format!("Fetching '{}' failed: HTTP 404 Not Found", url)),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,60 @@
+//! `--normalize-speakers`: `speakers.srt` opens lines with a tight hyphen,
+//! a spaced en dash and an em dash inside italics, and writes its speaker
+//! names in different cases and spacings.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview_lines(args: &[&str]) -> Vec<String> {
+    let output = submerger()
+        .arg(fixture("speakers.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "9", "--merge-strategy", "vertical"])
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+        .lines()
+        .filter(|line| !line.contains("-->"))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn dashes_become_one_style() {
+    let texts = preview_lines(&["--normalize-speakers"]);
+    assert_eq!(
+        texts[..3],
+        ["- Where to?", "- The station.", "Guten Morgen."]
+    );
+    assert!(texts.contains(&"<i>- Now?</i>".to_string()), "{:?}", texts);
+
+    let texts = preview_lines(&["--normalize-speakers", "hyphen-tight"]);
+    assert_eq!(texts[..2], ["-Where to?", "-The station."]);
+    let texts = preview_lines(&["--normalize-speakers", "en-dash"]);
+    assert!(texts.contains(&"<i>– Now?</i>".to_string()), "{:?}", texts);
+}
+
+#[test]
+fn speaker_names_are_written_alike() {
+    let texts = preview_lines(&["--normalize-speakers"]);
+    assert!(texts.contains(&"JOHN: Wait!".to_string()), "{:?}", texts);
+    assert!(texts.contains(&"- MARY: Why?".to_string()), "{:?}", texts);
+    assert!(
+        texts.contains(&"It is 10:30, Mary.".to_string()),
+        "{:?}",
+        texts
+    );
+
+    let texts = preview_lines(&["--normalize-speakers", "--speaker-case", "title"]);
+    assert!(texts.contains(&"John: Wait!".to_string()), "{:?}", texts);
+    assert!(texts.contains(&"- Mary: Why?".to_string()), "{:?}", texts);
+}
+
+#[test]
+fn text_is_untouched_without_the_flag() {
+    let texts = preview_lines(&[]);
+    assert_eq!(texts[..2], ["-Where to?", "–  The station."]);
+}
@@ -0,0 +1,62 @@
+//! Paths with spaces and non-ASCII characters: `ünï côde.srt` is a copy of
+//! `de.srt`.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use submerger::{parse_file, InputOptions, SubtitleMerger};
+
+#[test]
+fn merges_a_file_with_spaces_and_accents_in_its_name() {
+    let output_path = std::env::temp_dir().join("submerger odd päth.srt");
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("ünï côde.srt"))
+        .arg(&output_path)
+        .args(["--merge-strategy", "vertical"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("ünï côde.srt: 3"), "{}", stderr);
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    assert!(
+        content.contains("\nGood morning.\nGuten Morgen.\n"),
+        "{}",
+        content
+    );
+}
+
+#[test]
+fn the_library_takes_the_path_as_is() {
+    let mut merger = SubtitleMerger::builder().quiet(true).build();
+    merger
+        .add(fixture("ünï côde.srt"), &InputOptions::default())
+        .unwrap();
+    let sources = merger.sources();
+    assert_eq!(sources[0].path, fixture("ünï côde.srt").to_string_lossy());
+    assert_eq!(
+        sources[0].cue_count,
+        parse_file(fixture("de.srt")).unwrap().dialogs.len()
+    );
+}
+
+#[test]
+fn a_missing_odd_path_is_named_in_the_error() {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("nö such fïle.srt"))
+        .arg(std::env::temp_dir().join("submerger-odd-missing.srt"))
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr);
+    assert!(
+        stderr.contains(&format!(
+            "Cannot read '{}'",
+            fixture("nö such fïle.srt").display()
+        )),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,53 @@
+//! `--offset-preview`: the cues of `en.srt` and `de.srt` run from
+//! 00:00:01,000 to 00:00:09,000 before any transform.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview_row(args: &[&str]) -> String {
+    let output_path = std::env::temp_dir().join("submerger-offset-preview.srt");
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .arg("--offset-preview")
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(!output_path.exists(), "the output was written");
+    assert!(stderr.starts_with("input "), "{}", stderr);
+    let row = stderr.lines().find(|line| line.contains("de.srt"));
+    row.expect("no row for de.srt").to_string()
+}
+
+#[test]
+fn shows_the_anchored_offset() {
+    let row = preview_row(&["--anchor", "00:00:03,500=00:00:01,000"]);
+    assert!(
+        row.contains("00:00:01,000 -> 00:00:03,500  +2500ms"),
+        "{}",
+        row
+    );
+    assert!(
+        row.ends_with("00:00:09,000 -> 00:00:11,500  +2500ms"),
+        "{}",
+        row
+    );
+}
+
+#[test]
+fn shows_the_scale_before_the_offset() {
+    let row = preview_row(&["--scale", "1,1.5", "--anchor", "00:00:03,500=00:00:02,000"]);
+    assert!(
+        row.contains("00:00:01,000 -> 00:00:02,000  +1000ms"),
+        "{}",
+        row
+    );
+    assert!(
+        row.contains("00:00:09,000 -> 00:00:14,000  +5000ms"),
+        "{}",
+        row
+    );
+}
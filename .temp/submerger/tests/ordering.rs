@@ -0,0 +1,57 @@
+//! Cues sharing a start come out by input order, then by text, whatever
+//! their end; `same-times.srt` has a cue at the exact interval of one in
+//! `en.srt` and one 10ms before another.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(inputs: [&str; 2], args: &[&str]) -> Vec<String> {
+    let output_path = std::env::temp_dir().join(format!(
+        "submerger-ordering-{}-{}.srt",
+        inputs[0],
+        args.len()
+    ));
+    let output = submerger()
+        .args(inputs.map(fixture))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+        .split("\n\n")
+        .filter_map(|block| block.lines().nth(2).map(String::from))
+        .collect()
+}
+
+#[test]
+fn identical_intervals_follow_input_order() {
+    let texts = merge(["en.srt", "same-times.srt"], &[]);
+    assert_eq!(texts[..2], ["Good morning.", "(birds singing)"]);
+    let texts = merge(["same-times.srt", "en.srt"], &[]);
+    assert_eq!(texts[..2], ["(birds singing)", "Good morning."]);
+}
+
+#[test]
+fn order_is_restored_after_snapping_makes_starts_equal() {
+    let shots = std::env::temp_dir().join("submerger-ordering-shots.txt");
+    fs::write(&shots, "00:00:04,000\n").unwrap();
+    let texts = merge(
+        ["en.srt", "same-times.srt"],
+        &["--snap-to", shots.to_str().unwrap()],
+    );
+    fs::remove_file(shots).ok();
+    assert_eq!(
+        texts[2..4],
+        ["Where are you going?", "(footsteps)"],
+        "{:?}",
+        texts
+    );
+}
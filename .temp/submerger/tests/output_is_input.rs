@@ -0,0 +1,56 @@
+//! An output path naming one of the inputs, however spelled, is refused
+//! unless `--force` is given.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+#[test]
+fn overwriting_an_input_is_a_validation_failure() {
+    let dir = std::env::temp_dir().join("submerger-output-is-input");
+    fs::create_dir_all(&dir).unwrap();
+    let input_path = dir.join("en.srt");
+    fs::copy(fixture("en.srt"), &input_path).unwrap();
+    for output_path in [input_path.clone(), dir.join(".").join("en.srt")] {
+        let output = submerger()
+            .arg(&input_path)
+            .arg(fixture("de.srt"))
+            .arg(&output_path)
+            .run();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(5), "{}", stderr);
+        assert!(
+            stderr.contains("is the same file as input") && stderr.contains("use --force"),
+            "{}",
+            stderr
+        );
+    }
+    assert_eq!(
+        fs::read(&input_path).unwrap(),
+        fs::read(fixture("en.srt")).unwrap()
+    );
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn force_overwrites_the_input() {
+    let dir = std::env::temp_dir().join("submerger-output-is-input-forced");
+    fs::create_dir_all(&dir).unwrap();
+    let input_path = dir.join("en.srt");
+    fs::copy(fixture("en.srt"), &input_path).unwrap();
+    let output = submerger()
+        .arg(&input_path)
+        .arg(fixture("de.srt"))
+        .arg(&input_path)
+        .arg("--force")
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&input_path).unwrap();
+    assert!(content.contains("\nGuten Morgen.\n"), "{}", content);
+    fs::remove_dir_all(dir).ok();
+}
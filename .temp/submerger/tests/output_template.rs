@@ -0,0 +1,53 @@
+//! `--output-template` naming: the fixtures are copied under per-language
+//! names so `{base}` and `{langs}` have something to find.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+fn run_in(dir: &Path, template: &str) -> Output {
+    submerger()
+        .current_dir(dir)
+        .args([
+            "movie.en.srt",
+            "movie.de.srt",
+            "--output-template",
+            template,
+        ])
+        .run()
+}
+
+fn work_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("submerger-template-{}", name));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(fixture("en.srt"), dir.join("movie.en.srt")).unwrap();
+    fs::copy(fixture("de.srt"), dir.join("movie.de.srt")).unwrap();
+    dir
+}
+
+#[test]
+fn expands_base_and_langs() {
+    let dir = work_dir("expand");
+    let output = run_in(&dir, "{base}.{langs}.merged.srt");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dir.join("movie.en-de.merged.srt").exists());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn rejects_unknown_placeholders() {
+    let dir = work_dir("unknown");
+    let output = run_in(&dir, "{base}.{lang}.srt");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown placeholder '{lang}'"));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    fs::remove_dir_all(&dir).ok();
+}
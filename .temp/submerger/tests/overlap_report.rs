@@ -0,0 +1,54 @@
+//! `--overlap-report`: `en.srt` and `en.vtt` hold the same cue times, so
+//! they cover each other fully until one of them is moved.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn report(name: &str, args: &[&str]) -> Vec<Vec<String>> {
+    let report_path = std::env::temp_dir().join(format!("submerger-overlap-{}.tsv", name));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("en.vtt"))
+        .arg("--overlap-report")
+        .arg(&report_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&report_path).unwrap();
+    fs::remove_file(report_path).ok();
+    let mut lines = content.lines();
+    assert_eq!(
+        lines.next(),
+        Some("cue\tstart\tend\tduration_ms\toverlap_ms\toverlap")
+    );
+    lines
+        .map(|line| line.split('\t').map(String::from).collect())
+        .collect()
+}
+
+#[test]
+fn aligned_tracks_overlap_fully() {
+    let rows = report("aligned", &[]);
+    assert_eq!(rows.len(), 3);
+    for row in &rows {
+        assert_eq!(row[3], row[4], "{:?}", row);
+        assert_eq!(row[5], "1.000", "{:?}", row);
+    }
+    assert_eq!(rows[0][..3], ["1", "00:00:01,000", "00:00:03,500"]);
+}
+
+#[test]
+fn an_offset_track_overlaps_less() {
+    // en.vtt one second late: the first cue keeps 1.5s of its 2.5s.
+    let rows = report("offset", &["--anchor", "00:00:02,000=00:00:01,000"]);
+    assert_eq!(rows[0][3..], ["2500", "1500", "0.600"]);
+    for row in &rows {
+        assert!(row[5].parse::<f64>().unwrap() < 0.8, "{:?}", row);
+    }
+}
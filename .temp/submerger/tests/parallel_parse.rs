@@ -0,0 +1,83 @@
+#![cfg(feature = "rayon")]
+//! Times `add_all`, which parses its inputs in parallel with the `rayon`
+//! feature, against adding the same inputs one by one. Ignored by default:
+//! run with `cargo test --release --features rayon --test parallel_parse --
+//! --ignored --nocapture` to print the figures.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+use submerger::{InputOptions, SubtitleMerger};
+
+const INPUTS: usize = 8;
+const CUES_PER_INPUT: usize = 50_000;
+
+fn write_inputs() -> Vec<PathBuf> {
+    let dir = std::env::temp_dir().join("submerger-parallel-parse");
+    fs::create_dir_all(&dir).unwrap();
+    (0..INPUTS)
+        .map(|input| {
+            let mut content = String::new();
+            for i in 0..CUES_PER_INPUT {
+                let start = i * 2000 + input * 10;
+                write!(
+                    content,
+                    "{}\n{} --> {}\nLine {} of input {}\n\n",
+                    i + 1,
+                    submerger::format_timestamp(start as i64),
+                    submerger::format_timestamp(start as i64 + 1500),
+                    i,
+                    input
+                )
+                .unwrap();
+            }
+            let path = dir.join(format!("input-{}.srt", input));
+            fs::write(&path, content).unwrap();
+            path
+        })
+        .collect()
+}
+
+#[test]
+#[ignore]
+fn parallel_parse_against_one_by_one() {
+    let inputs = write_inputs();
+    let jobs: Vec<(PathBuf, InputOptions)> = inputs
+        .iter()
+        .map(|path| (path.clone(), InputOptions::default()))
+        .collect();
+
+    let mut one_by_one = SubtitleMerger::builder().quiet(true).build();
+    let started = Instant::now();
+    for (path, options) in &jobs {
+        one_by_one.add(path, options).unwrap();
+    }
+    let sequential = started.elapsed();
+
+    let mut parallel = SubtitleMerger::builder().quiet(true).build();
+    let started = Instant::now();
+    parallel.add_all(&jobs).unwrap();
+    let concurrent = started.elapsed();
+
+    let cue_counts = |merger: &SubtitleMerger| -> Vec<(String, usize)> {
+        merger
+            .sources()
+            .into_iter()
+            .map(|source| (source.path, source.cue_count))
+            .collect()
+    };
+    assert_eq!(cue_counts(&parallel), cue_counts(&one_by_one));
+    println!(
+        "{} inputs of {} cues on {} threads: one by one {:?}, add_all {:?}, {:.2}x",
+        INPUTS,
+        CUES_PER_INPUT,
+        std::thread::available_parallelism().map_or(1, |n| n.get()),
+        sequential,
+        concurrent,
+        sequential.as_secs_f64() / concurrent.as_secs_f64()
+    );
+    for path in inputs {
+        fs::remove_file(path).ok();
+    }
+}
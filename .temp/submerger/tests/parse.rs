@@ -0,0 +1,150 @@
+//! Parsing on its own through the library functions, without a merger.
+
+mod common;
+
+use common::fixture;
+use std::time::{Duration, Instant};
+use submerger::{parse_file, parse_str, Cue, MergeError};
+
+#[test]
+fn parses_a_fixture_file() {
+    let path = fixture("en.srt");
+    let subtitle = parse_file(&path).unwrap();
+    assert_eq!(subtitle.format, "srt");
+    assert_eq!(subtitle.encoding, "UTF-8");
+    assert_eq!(subtitle.path, path.to_string_lossy());
+    assert_eq!(subtitle.dialogs.len(), 3);
+    let dialog = &subtitle.dialogs[&4000];
+    assert_eq!(
+        (dialog.end, dialog.text.as_str()),
+        (6250, "Where are you going?")
+    );
+    assert_eq!(dialog.index, Some(2));
+}
+
+#[test]
+fn parses_ass_text_with_styles_and_comments() {
+    let content = "[Script Info]\nPlayResX: 640\nPlayResY: 360\n\n\
+                   [V4+ Styles]\nFormat: Name, Bold, Italic\nStyle: Sign,-1,0\n\n\
+                   [Events]\nFormat: Layer, Start, End, Style, Text\n\
+                   Dialogue: 0,0:00:01.00,0:00:02.50,Sign,Exit, left\n\
+                   Comment: 0,0:00:03.00,0:00:04.00,Sign,timing note\n";
+    let subtitle = parse_str(content, "ass").unwrap();
+    assert_eq!(subtitle.play_res, Some((640, 360)));
+    assert!(subtitle.styles["Sign"].bold);
+    assert_eq!(subtitle.dialogs[&1000].text, "Exit, left");
+    assert_eq!(subtitle.dialogs[&1000].style.as_deref(), Some("Sign"));
+    assert_eq!(subtitle.comments[&3000].text, "timing note");
+    assert!(subtitle.extra_sections.is_empty());
+
+    let content = format!("{}\n[Aegisub Project Garbage]\nActive Line: 1\n\n", content);
+    let subtitle = parse_str(&content, "ass").unwrap();
+    assert_eq!(
+        subtitle.extra_sections,
+        [(
+            "Aegisub Project Garbage".to_string(),
+            vec!["Active Line: 1".to_string()]
+        )]
+    );
+}
+
+#[test]
+fn skips_junk_before_the_first_cue() {
+    let path = fixture("junk.srt");
+    let subtitle = parse_file(&path).unwrap();
+    assert_eq!(subtitle.dialogs.len(), 2);
+    assert_eq!(subtitle.dialogs[&1000].index, Some(1));
+    assert_eq!(subtitle.dialogs[&1000].text, "Hello there.");
+    let preamble = subtitle.preamble.unwrap();
+    assert!(preamble.ends_with("Language=en"), "{}", preamble);
+    assert!(!preamble.contains("-->"), "{}", preamble);
+
+    let clean = parse_file(path.with_file_name("en.srt")).unwrap();
+    assert_eq!(clean.preamble, None);
+}
+
+#[test]
+fn parses_a_huge_drawing_line_quickly() {
+    let drawing = "l 10 20 ".repeat(12_500);
+    let content = format!(
+        "[Events]\nFormat: Layer, Start, End, Style, Text\n\
+         Dialogue: 0,0:00:01.00,0:00:02.00,Default,{{\\p1}}m 0 0 {}{{\\p0}}\n",
+        drawing
+    );
+    assert!(content.len() > 100_000);
+    let started = Instant::now();
+    let subtitle = parse_str(&content, "ass").unwrap();
+    assert!(
+        started.elapsed() < Duration::from_secs(1),
+        "{:?}",
+        started.elapsed()
+    );
+    assert!(subtitle.dialogs[&1000].text.ends_with("{\\p0}"));
+}
+
+#[test]
+fn parses_vtt_with_and_without_hours() {
+    let path = fixture("en.vtt");
+    let subtitle = parse_file(&path).unwrap();
+    assert_eq!(subtitle.format, "vtt");
+    let mut cues: Vec<_> = subtitle
+        .dialogs
+        .iter()
+        .map(|(&start, dialog)| (start, dialog.end, dialog.text.as_str(), dialog.index))
+        .collect();
+    cues.sort();
+    assert_eq!(
+        cues,
+        [
+            (1000, 3500, "Good morning.", Some(1)),
+            (4000, 6250, "Where are you going?", None),
+            (7120, 9000, "To the station.", None),
+        ]
+    );
+}
+
+#[test]
+fn reports_parse_and_io_errors() {
+    assert!(matches!(
+        parse_str("hello", "txt"),
+        Err(MergeError::Parse(_))
+    ));
+    assert!(matches!(
+        parse_str("1\n00:61:00,000 --> 00:62:00,000\nHi\n", "srt"),
+        Err(MergeError::Parse(message)) if message.contains("minutes '61'")
+    ));
+    assert!(matches!(
+        parse_file("tests/fixtures/missing.srt"),
+        Err(MergeError::Io(_))
+    ));
+}
+
+#[test]
+fn cues_sort_by_start_then_end_then_text() {
+    let expected = vec![
+        Cue::new(1000, 2000, "A"),
+        Cue::new(1000, 2000, "B"),
+        Cue::new(1000, 3000, "A"),
+        Cue::new(1500, 1800, "A"),
+        Cue::new(4000, 4500, "Z"),
+    ];
+    let mut shuffled = vec![
+        expected[3].clone(),
+        expected[1].clone(),
+        expected[4].clone(),
+        expected[0].clone(),
+        expected[2].clone(),
+    ];
+    shuffled.sort();
+    assert_eq!(shuffled, expected);
+
+    let path = fixture("en.srt");
+    assert_eq!(
+        parse_file(&path).unwrap().cues(),
+        [
+            Cue::new(1000, 3500, "Good morning."),
+            Cue::new(4000, 6250, "Where are you going?"),
+            Cue::new(7120, 9000, "To the station."),
+        ]
+    );
+}
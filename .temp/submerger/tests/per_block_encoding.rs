@@ -0,0 +1,52 @@
+//! `--per-block-encoding`: the first two cues of `two-encodings.srt` are
+//! UTF-8 and the last two Windows-1252, as if two files had been joined.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn preview(args: &[&str]) -> String {
+    let output = submerger()
+        .arg(fixture("two-encodings.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "9"])
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn decodes_each_block_in_its_own_encoding() {
+    let preview = preview(&["--per-block-encoding"]);
+    assert!(
+        preview.contains(
+            "two-encodings.srt' mixes encodings: UTF-8 in 2 blocks, windows-1252 in 1 blocks"
+        ),
+        "{}",
+        preview
+    );
+    assert!(preview.contains("Café au lait"), "{}", preview);
+    assert!(preview.contains("À bientôt"), "{}", preview);
+    assert!(preview.contains("Garçon, l'été"), "{}", preview);
+}
+
+#[test]
+fn one_decoder_garbles_the_utf8_half() {
+    let preview = preview(&[]);
+    assert!(preview.contains("CafÃ© au lait"), "{}", preview);
+    assert!(preview.contains("Garçon, l'été"), "{}", preview);
+    assert!(!preview.contains("mixes encodings"), "{}", preview);
+}
+
+#[test]
+fn library_reports_the_encodings_in_order() {
+    let bytes = std::fs::read(fixture("two-encodings.srt")).unwrap();
+    let (content, encodings) = submerger::decode_blocks(&bytes);
+    assert_eq!(encodings, [("UTF-8", 2), ("windows-1252", 1)]);
+    assert_eq!(
+        submerger::parse_str(&content, "srt").unwrap().cues().len(),
+        4
+    );
+}
@@ -0,0 +1,52 @@
+//! ASS inputs of different `PlayRes`: `sign-1080p.ass` is 1920x1080 and
+//! `sign-360p.ass` is 640x360, each with one `\pos` sign.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+/// The merged script and stderr.
+fn merge(name: &str, args: &[&str]) -> (String, String) {
+    let output_path = std::env::temp_dir().join(name);
+    let output = submerger()
+        .arg(fixture("sign-1080p.ass"))
+        .arg(fixture("sign-360p.ass"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    (content, stderr)
+}
+
+#[test]
+fn later_positions_are_scaled_to_the_first_resolution() {
+    let (content, stderr) = merge("submerger-play-res.ass", &[]);
+    assert!(
+        content.contains("\nPlayResX: 1920\nPlayResY: 1080\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("{\\pos(960,540)}Exit\n"), "{}", content);
+    assert!(content.contains("{\\pos(960,270)}Ausgang\n"), "{}", content);
+    assert!(!stderr.contains("Warning"), "{}", stderr);
+}
+
+#[test]
+fn keep_positions_warns_and_leaves_them_alone() {
+    let (content, stderr) = merge("submerger-play-res-kept.ass", &["--keep-positions"]);
+    assert!(
+        content.contains("\nPlayResX: 1920\nPlayResY: 1080\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("{\\pos(320,90)}Ausgang\n"), "{}", content);
+    assert!(
+        stderr.contains("uses PlayRes 640x360 but the merged script uses 1920x1080"),
+        "{}",
+        stderr
+    );
+}
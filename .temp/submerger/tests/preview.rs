@@ -0,0 +1,34 @@
+//! `--preview N`: the first N merged cues in time order on stderr, and no
+//! file unless an output is named. `en.srt`, given second and anchored
+//! 500ms earlier, starts before `de.srt`.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+#[test]
+fn prints_the_first_cues_in_time_order_without_writing() {
+    let dir = std::env::temp_dir().join("submerger-preview");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    let output = submerger()
+        .current_dir(&dir)
+        .arg(fixture("de.srt"))
+        .arg(fixture("en.srt"))
+        .args(["--preview", "2", "--anchor", "00:00:00,500=00:00:01,000"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(
+        stderr.lines().collect::<Vec<_>>(),
+        [
+            "   1  00:00:00,500 --> 00:00:03,000",
+            "      Good morning.",
+            "   2  00:00:01,000 --> 00:00:03,400",
+            "      Guten Morgen.",
+        ]
+    );
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(dir).ok();
+}
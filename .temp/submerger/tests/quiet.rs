@@ -0,0 +1,55 @@
+//! `--quiet`: informational output goes to stderr, and `-q` silences it,
+//! leaving only what was asked for.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn merge(args: &[&str]) -> Output {
+    let output_path = std::env::temp_dir().join("submerger-quiet.srt");
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("junk.srt"))
+        .arg(&output_path)
+        .args(["--force"])
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    std::fs::remove_file(output_path).ok();
+    output
+}
+
+#[test]
+fn success_messages_go_to_stderr() {
+    let output = merge(&[]);
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("created successfully"), "{}", stderr);
+    assert!(stderr.contains("skipped 4 lines"), "{}", stderr);
+}
+
+#[test]
+fn quiet_prints_nothing_for_a_file_output() {
+    let output = merge(&["--quiet"]);
+    assert!(output.stdout.is_empty());
+    assert!(
+        output.stderr.is_empty(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn quiet_keeps_output_asked_for() {
+    let output = merge(&["-q", "--preview", "1"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr,
+        "   1  00:00:01,000 --> 00:00:03,500\n      Good morning.\n"
+    );
+}
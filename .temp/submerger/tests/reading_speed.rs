@@ -0,0 +1,43 @@
+//! `--reading-speed-warn`: of the six cues `en.srt` and `de.srt` merge to,
+//! the third ("Where are you going?") and fifth ("To the station.") read at
+//! over 7 characters per second.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn stderr(args: &[&str]) -> String {
+    let output_path =
+        std::env::temp_dir().join(format!("submerger-reading-speed{}.srt", args.join("")));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    std::fs::remove_file(output_path).ok();
+    stderr
+}
+
+#[test]
+fn warns_about_each_cue_over_the_limit() {
+    let stderr = stderr(&["--reading-speed-warn", "7"]);
+    let warnings: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.starts_with("Warning:"))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "Warning: cue 3 reads at 8.9 cps, 1.9 over the 7 cps limit",
+            "Warning: cue 5 reads at 8.0 cps, 1.0 over the 7 cps limit",
+        ]
+    );
+}
+
+#[test]
+fn no_limit_no_warnings() {
+    assert!(!stderr(&[]).contains("Warning:"));
+}
@@ -0,0 +1,26 @@
+//! `--romanize` stacks a transliteration under the marked input; only built
+//! with the `translit` feature.
+#![cfg(feature = "translit")]
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+#[test]
+fn cyrillic_line_gains_a_romanized_line() {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("ru.srt"))
+        .args(["--preview", "6", "--romanize", "2"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("      Доброе утро.\n      Dobroe utro.\n"),
+        "{}",
+        stderr
+    );
+    // Only the marked input is romanized, and Latin text is left alone.
+    assert!(stderr.contains("      Good morning.\n   2"), "{}", stderr);
+    assert_eq!(stderr.matches("To the station.").count(), 2, "{}", stderr);
+}
@@ -0,0 +1,43 @@
+//! `--sample-every`: `en.srt`, `de.srt` and `ru.srt` merge into nine cues,
+//! three at each of 1s, 4s and 7.12s, the English one first each time.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(sample_every: &str) -> String {
+    let output_path =
+        std::env::temp_dir().join(format!("submerger-sample-every-{}.srt", sample_every));
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .arg("--add-input")
+        .arg(fixture("ru.srt"))
+        .args(["--sample-every", sample_every])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+#[test]
+fn keeps_every_third_cue() {
+    assert_eq!(
+        merge("3"),
+        "1\n00:00:01,000 --> 00:00:03,500\nGood morning.\n\n\
+         2\n00:00:04,000 --> 00:00:06,250\nWhere are you going?\n\n\
+         3\n00:00:07,120 --> 00:00:09,000\nTo the station.\n\n"
+    );
+}
+
+#[test]
+fn one_keeps_them_all() {
+    assert_eq!(merge("1").matches(" --> ").count(), 9);
+}
@@ -0,0 +1,64 @@
+//! `--scale`: `hour.srt` has one cue at 01:00:00,000, where a small factor
+//! is easy to read off; factors that are not finite and above zero are
+//! refused.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use submerger::{InputOptions, MergeError, SubtitleMerger};
+
+#[test]
+fn scales_a_cue_an_hour_in_exactly() {
+    let output_path = std::env::temp_dir().join("submerger-scale.srt");
+    let output = submerger()
+        .arg(fixture("hour.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--scale", "1.001"])
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    assert!(
+        content.contains("\n01:00:03,600 --> 01:00:05,602\nAn hour in.\n"),
+        "{}",
+        content
+    );
+}
+
+#[test]
+fn rejects_factors_that_are_not_finite_and_positive() {
+    for factor in ["0", "-1", "NaN", "inf", "1,0"] {
+        let output = submerger()
+            .arg(fixture("hour.srt"))
+            .arg(fixture("de.srt"))
+            .arg(std::env::temp_dir().join("submerger-scale-invalid.srt"))
+            .arg(format!("--scale={}", factor))
+            .run();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(5), "{}: {}", factor, stderr);
+        assert!(
+            stderr.contains("must be a finite number greater than 0"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn the_library_refuses_a_zero_scale() {
+    let mut merger = SubtitleMerger::builder().quiet(true).build();
+    let options = InputOptions {
+        scale: 0.0,
+        ..InputOptions::default()
+    };
+    assert!(matches!(
+        merger.add(fixture("hour.srt"), &options),
+        Err(MergeError::Validation(_))
+    ));
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "serve")]
+//! `serve`: the preview endpoint answers with the merge of its inputs as
+//! JSON, re-merged with the options of each query.
+
+mod common;
+
+use common::{fixture, submerger};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Stdio};
+
+struct Server {
+    child: Child,
+    address: String,
+}
+
+impl Server {
+    fn start() -> Server {
+        let mut child = submerger()
+            .arg("serve")
+            .arg(fixture("en.srt"))
+            .arg(fixture("de.srt"))
+            .args(["--port", "0"])
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to run submerger");
+        let mut line = String::new();
+        BufReader::new(child.stderr.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let address = line
+            .trim()
+            .strip_prefix("Serving the merge preview at http://")
+            .and_then(|rest| rest.strip_suffix('/'))
+            .unwrap_or_else(|| panic!("unexpected output: {}", line))
+            .to_string();
+        Server { child, address }
+    }
+
+    fn get(&self, target: &str) -> String {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+#[test]
+fn returns_the_merged_cues_as_json() {
+    let server = Server::start();
+    let response = server.get("/cues.json?strategy=vertical&bundle-window=200ms");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(
+        response.contains("Content-Type: application/json"),
+        "{}",
+        response
+    );
+    assert!(
+        response.contains(
+            r#"{"start": 4000, "end": 6300, "text": "Where are you going?\nWohin gehst du?", "sources": [0, 1]}"#
+        ),
+        "{}",
+        response
+    );
+    assert_eq!(response.matches("\"start\"").count(), 3, "{}", response);
+
+    let shifted = server.get("/cues.json?strategy=vertical&offset=0,-100");
+    assert!(
+        shifted.contains(r#"{"start": 4000, "end": 6250, "text": "Where are you going?\nWohin gehst du?", "sources": [0, 1]}"#),
+        "{}",
+        shifted
+    );
+}
+
+#[test]
+fn serves_the_page_and_rejects_bad_queries() {
+    let server = Server::start();
+    assert!(server.get("/").contains("<title>submerger preview</title>"));
+    assert!(server
+        .get("/cues.json?strategy=diagonal")
+        .starts_with("HTTP/1.1 400 Bad Request"));
+    assert!(server.get("/missing").starts_with("HTTP/1.1 404 Not Found"));
+}
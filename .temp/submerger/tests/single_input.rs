@@ -0,0 +1,88 @@
+//! Merges left with a single input, here by `--keep-only-sources` and by
+//! `--dedup-inputs`, write that input alone with its transforms applied;
+//! a run naming no inputs is refused.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+use submerger::{parse_file, Cue};
+
+fn merge(output: &str, args: &[&str]) -> (std::process::Output, std::path::PathBuf) {
+    let output_path = std::env::temp_dir().join(output);
+    let result = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    (result, output_path)
+}
+
+#[test]
+fn one_input_is_reserialized_with_its_transforms() {
+    let (output, path) = merge(
+        "submerger-single-input.ass",
+        &["--keep-only-sources", "2", "--scale", "2"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("3 cues written"), "{}", stderr);
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with("[Script Info]"), "{}", content);
+    assert_eq!(
+        parse_file(&path).unwrap().cues(),
+        [
+            Cue::new(2000, 6800, "Guten Morgen."),
+            Cue::new(8200, 12600, "Wohin gehst du?"),
+            Cue::new(14240, 18000, "Zum Bahnhof."),
+        ]
+    );
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn a_duplicated_input_counts_once() {
+    let path = std::env::temp_dir().join("submerger-single-input-dedup.srt");
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("en.srt"))
+        .arg(&path)
+        .arg("--dedup-inputs")
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        parse_file(&path).unwrap().cues(),
+        parse_file(fixture("en.srt")).unwrap().cues()
+    );
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn one_input_without_transforms_passes_through() {
+    let (output, path) = merge("submerger-single-input.srt", &["--keep-only-sources", "1"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let fixture = fixture("en.srt");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap().trim_end(),
+        fs::read_to_string(fixture).unwrap().trim_end()
+    );
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn no_inputs_is_a_usage_error() {
+    let output = submerger().run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(5), "{}", stderr);
+    assert!(stderr.contains("<input1>"), "{}", stderr);
+    assert!(stderr.contains("Usage: submerger"), "{}", stderr);
+}
@@ -0,0 +1,63 @@
+//! The `stats` subcommand against fixtures with hand-computed statistics.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn stats(name: &str, args: &[&str]) -> Output {
+    submerger().arg("stats").arg(fixture(name)).args(args).run()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn json_matches_the_fixture() {
+    let json = stdout(&stats("en.srt", &["--json"]));
+    for field in [
+        "\"format\": \"srt\"",
+        "\"encoding\": \"UTF-8\"",
+        "\"cues\": 3",
+        "\"on_screen_ms\": 6630",
+        "\"average_ms\": 2210",
+        "\"median_ms\": 2250",
+        "\"shortest_ms\": 1880",
+        "\"shortest_start_ms\": 7120",
+        "\"longest_ms\": 2500",
+        "\"longest_start_ms\": 1000",
+        "\"gaps\": 2",
+        "\"total_gap_ms\": 1370",
+        "\"longest_gap_ms\": 870",
+    ] {
+        assert!(json.contains(field), "missing {}: {}", field, json);
+    }
+}
+
+#[test]
+fn overlapping_cues_count_once_on_screen() {
+    let text = stdout(&stats("close.srt", &[]));
+    assert!(text.contains("  cues:         4\n"), "{}", text);
+    assert!(text.contains("  on screen:    00:00:02,000\n"), "{}", text);
+    assert!(
+        text.contains("  gaps:         1, 00:00:01,000 in total, longest 00:00:01,000\n"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("  shortest:     00:00:00,004 (cue at 00:00:10,000)\n"),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn files_without_cues_fail() {
+    assert_eq!(stats("notes.txt", &[]).status.code(), Some(2));
+}
@@ -0,0 +1,39 @@
+//! An output path of `-` writes the merge to stdout and no file.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+#[test]
+fn dash_writes_the_merge_to_stdout() {
+    let dir = std::env::temp_dir().join("submerger-stdout");
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = submerger()
+        .current_dir(&dir)
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["-", "--keep-only-sources", "1"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1\n00:00:01,000 --> 00:00:03,500\nGood morning.\n\n\
+         2\n00:00:04,000 --> 00:00:06,250\nWhere are you going?\n\n\
+         3\n00:00:07,120 --> 00:00:09,000\nTo the station.\n\n"
+    );
+    assert!(!stderr.contains("created successfully"), "{}", stderr);
+    assert!(!dir.join("-").exists());
+}
+
+#[test]
+fn dash_takes_the_format_from_format_out() {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["-", "--format-out", "vtt", "--quiet"])
+        .run();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("WEBVTT\n"));
+    assert!(output.stderr.is_empty());
+}
@@ -0,0 +1,53 @@
+//! `--strict-timestamps`: inputs with impossible times load with warnings by
+//! default but fail under the flag, naming the offending line.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn preview(input: &str, args: &[&str]) -> Output {
+    submerger()
+        .arg(fixture(input))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "0"])
+        .args(args)
+        .run()
+}
+
+fn assert_rejected(output: &Output, message: &str) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr);
+    assert!(stderr.contains(message), "{}", stderr);
+}
+
+#[test]
+fn lenient_by_default() {
+    assert!(preview("swapped.srt", &[]).status.success());
+    assert!(preview("out-of-order.srt", &[]).status.success());
+}
+
+#[test]
+fn rejects_a_cue_ending_before_it_starts() {
+    assert_rejected(
+        &preview("swapped.srt", &["--strict-timestamps"]),
+        "line 6: cue ends at 00:00:04,000 before it starts at 00:00:06,250",
+    );
+}
+
+#[test]
+fn rejects_a_cue_starting_before_the_previous_one() {
+    assert_rejected(
+        &preview("out-of-order.srt", &["--strict-timestamps"]),
+        "line 10: cue starts at 00:00:04,000, before the previous cue's start at 00:00:04,100",
+    );
+    let output = preview(
+        "out-of-order.srt",
+        &["--strict-timestamps", "--strict-tolerance", "100ms"],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
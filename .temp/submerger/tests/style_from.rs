@@ -0,0 +1,67 @@
+//! `--style-from`: `complex.ass` typesets the same three lines as `en.srt`
+//! in its `Main` style, with a `Sign` event overlapping the first two and
+//! another, placed and colored, over the third.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(output: &str) -> (std::process::Output, std::path::PathBuf) {
+    let output_path = std::env::temp_dir().join(output);
+    let result = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--keep-only-sources", "1", "--style-from"])
+        .arg(fixture("complex.ass"))
+        .run();
+    (result, output_path)
+}
+
+#[test]
+fn srt_text_takes_the_styles_of_overlapping_events() {
+    let (output, path) = merge("submerger-style-from.ass");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&path).unwrap();
+    let events: Vec<&str> = content
+        .lines()
+        .filter(|line| line.starts_with("Dialogue: "))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            "Dialogue: 0,0:00:01.00,0:00:03.50,Main,,0,0,0,,Good morning.",
+            "Dialogue: 0,0:00:04.00,0:00:06.25,Main,,0,0,0,,Where are you going?",
+            "Dialogue: 0,0:00:07.12,0:00:09.00,Sign,,0,0,0,,{\\an7\\pos(32,32)}To the station.",
+        ]
+    );
+    assert!(
+        content.contains("PlayResX: 1280\nPlayResY: 720\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("Style: Sign,Noto Serif,36,"),
+        "{}",
+        content
+    );
+    assert!(!content.contains("Style: Default,"), "{}", content);
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn needs_ass_output() {
+    let (output, path) = merge("submerger-style-from.srt");
+    assert_eq!(output.status.code(), Some(5));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--style-from writes styled ASS"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!path.exists());
+}
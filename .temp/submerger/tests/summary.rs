@@ -0,0 +1,49 @@
+//! The summary printed to stderr after a merge: how many cues were written,
+//! the span they cover and how many cues each input contributed to.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn summary(name: &str, args: &[&str]) -> String {
+    let output_path = std::env::temp_dir().join(name);
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    std::fs::remove_file(output_path).ok();
+    stderr
+}
+
+#[test]
+fn reports_the_count_span_and_each_input() {
+    let output_path = std::env::temp_dir().join("submerger-summary.srt");
+    assert_eq!(
+        summary("submerger-summary.srt", &[]),
+        format!(
+            "'{}' created successfully.\n\
+             6 cues written, spanning 00:00:01,000 --> 00:00:09,000\n  \
+             {}: 3, {}: 3\n",
+            output_path.display(),
+            fixture("en.srt").display(),
+            fixture("de.srt").display()
+        )
+    );
+}
+
+#[test]
+fn one_cue_is_singular() {
+    let stderr = summary(
+        "submerger-summary-one.srt",
+        &["--merge-strategy", "vertical", "--bundle-window", "9s"],
+    );
+    assert!(
+        stderr.contains("\n1 cue written, spanning 00:00:01,000 --> 00:00:09,000\n"),
+        "{}",
+        stderr
+    );
+}
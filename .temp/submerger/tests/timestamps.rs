@@ -0,0 +1,106 @@
+//! Table-driven checks of the shared timestamp parser, exercised through
+//! `--shift-first-cue-to`: valid spellings must move the first cue to the
+//! expected SRT time, invalid ones must be rejected naming the bad component.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn shift_first_cue_to(value: &str) -> Output {
+    submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "1", "--shift-first-cue-to", value])
+        .run()
+}
+
+#[test]
+fn accepts_srt_vtt_and_ass_spellings() {
+    let cases = [
+        ("00:00:01,500", "00:00:01,500"),
+        ("00:00:01.500", "00:00:01,500"),
+        ("01:02.250", "00:01:02,250"),
+        ("0:00:01.50", "00:00:01,500"),
+        ("0:00:01.5", "00:00:01,500"),
+        ("1:02:03", "01:02:03,000"),
+        ("00:00:01,123456", "00:00:01,123"),
+        ("123:00:00,000", "123:00:00,000"),
+    ];
+    for (value, expected) in cases {
+        let output = shift_first_cue_to(value);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{} rejected: {}", value, stderr);
+        assert!(
+            stderr.contains(&format!("{} -->", expected)),
+            "{} did not start at {}: {}",
+            value,
+            expected,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn rejects_malformed_components() {
+    let cases = [
+        (":01", "minutes is missing"),
+        ("00:00:01;500", "seconds '01;500' is not a number"),
+        ("00:61:00,000", "minutes '61' is not below 60"),
+        ("00:00:60,000", "seconds '60' is not below 60"),
+        ("00:00:01,5x0", "fraction '5x0' is not a number"),
+        ("00:00:01,", "fraction is missing"),
+        ("00:00:01,500.1", "fraction '500.1' is not a number"),
+        ("1:00:00:00", "clock '1:00:00:00' is not [HH:]MM:SS"),
+        ("١٢:00:00", "hours '١٢' is not a number"),
+        ("aa:00:00", "hours 'aa' is not a number"),
+    ];
+    for (value, expected) in cases {
+        let output = shift_first_cue_to(value);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "{} was accepted", value);
+        assert!(
+            stderr.contains(expected),
+            "{} failed without '{}': {}",
+            value,
+            expected,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn rejects_hours_too_large_to_count_in_milliseconds() {
+    let hours = "99999999999999999";
+    let expected = format!("hours '{}' is too large", hours);
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "1", "--media-duration"])
+        .arg(format!("{}:00:00,000", hours))
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(5), "{}", stderr);
+    assert!(stderr.contains(&expected), "{}", stderr);
+
+    // WebVTT hours have no digit limit, so the parser meets them too.
+    let vtt = std::env::temp_dir().join("submerger-huge-hours.vtt");
+    std::fs::write(
+        &vtt,
+        "WEBVTT\n\n9999999999999999:00:00.000 --> 9999999999999999:00:01.000\nHello\n",
+    )
+    .unwrap();
+    let output = submerger()
+        .arg(&vtt)
+        .arg(fixture("de.srt"))
+        .args(["--preview", "1"])
+        .run();
+    std::fs::remove_file(vtt).ok();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr);
+    assert!(
+        stderr.contains("hours '9999999999999999' is too large"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,95 @@
+//! `--tolerance`: the default window of `--bundle-window` and
+//! `--collapse-gap` unless each is given. The second cues of `en.srt` and
+//! `de.srt` start 100ms apart; `stutter.srt` repeats a line after gaps of
+//! 0ms, 50ms and 500ms.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn window_report(args: &[&str]) -> String {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "0", "--merge-strategy", "vertical"])
+        .arg("--merge-window-report")
+        .args(args)
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+fn collapsed_timings(name: &str, args: &[&str]) -> Vec<String> {
+    let output_path = std::env::temp_dir().join(format!("submerger-tolerance-{}.srt", name));
+    let output = submerger()
+        .arg(fixture("stutter.srt"))
+        .arg(fixture("de.srt"))
+        .arg(&output_path)
+        .args(["--keep-only-sources", "1", "--collapse-consecutive"])
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+        .lines()
+        .filter(|line| line.contains(" --> "))
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn bundle_window_defaults_to_the_tolerance() {
+    let report = window_report(&["--tolerance", "150ms"]);
+    assert!(
+        report.contains("grouped 2 cues starting within 100ms, window 150ms"),
+        "{}",
+        report
+    );
+}
+
+#[test]
+fn bundle_window_wins_over_the_tolerance() {
+    let report = window_report(&["--tolerance", "150ms", "--bundle-window", "50ms"]);
+    assert!(
+        report.contains("not grouped; the next cue starts 100ms later, window 50ms"),
+        "{}",
+        report
+    );
+}
+
+#[test]
+fn collapse_gap_defaults_to_the_tolerance() {
+    assert_eq!(
+        collapsed_timings("default", &[]),
+        [
+            "00:00:01,000 --> 00:00:04,000",
+            "00:00:04,500 --> 00:00:06,000",
+            "00:00:07,120 --> 00:00:09,000",
+        ]
+    );
+    assert_eq!(
+        collapsed_timings("global", &["--tolerance", "600ms"]),
+        [
+            "00:00:01,000 --> 00:00:06,000",
+            "00:00:07,120 --> 00:00:09,000",
+        ]
+    );
+    assert_eq!(
+        collapsed_timings(
+            "specific",
+            &["--tolerance", "600ms", "--collapse-gap", "100ms"]
+        ),
+        [
+            "00:00:01,000 --> 00:00:04,000",
+            "00:00:04,500 --> 00:00:06,000",
+            "00:00:07,120 --> 00:00:09,000",
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+//! `--keep-unknown-sections`: sections the merge has no use for, such as
+//! Aegisub's project state, are dropped unless asked for, and then the
+//! first input to have a section of a name wins.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(args: &[&str]) -> String {
+    let output_path =
+        std::env::temp_dir().join(format!("submerger-unknown-sections-{}.ass", args.len()));
+    let output = submerger()
+        .arg(fixture("garbage.ass"))
+        .arg(fixture("garbage-b.ass"))
+        .arg(&output_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    content
+}
+
+#[test]
+fn dropped_by_default() {
+    let content = merge(&[]);
+    assert!(!content.contains("[Aegisub"), "{}", content);
+    assert!(!content.contains("movie.mkv"), "{}", content);
+}
+
+#[test]
+fn kept_with_the_flag_first_input_winning() {
+    let content = merge(&["--keep-unknown-sections"]);
+    assert!(
+        content.contains(
+            "\n[Aegisub Project Garbage]\nAudio File: movie.mkv\nVideo File: movie.mkv\nActive Line: 2\n"
+        ),
+        "{}",
+        content
+    );
+    assert!(!content.contains("other.mkv"), "{}", content);
+    assert!(
+        content.ends_with(
+            "\n[Aegisub Extradata]\nData: 1,_aegi_perspective_ambient_plane,e#3A0#3A0#3A0\n"
+        ),
+        "{}",
+        content
+    );
+    let events = content.find("[Events]").unwrap();
+    assert!(content.find("[Aegisub Project Garbage]").unwrap() > events);
+}
@@ -0,0 +1,77 @@
+//! `--vocab`: listed words are wrapped wherever they appear as whole words,
+//! in any case, in each output format's own way; other words, parts of
+//! words and the inside of tags are left alone.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::fs;
+
+fn merge(inputs: [&str; 2], extension: &str, args: &[&str]) -> String {
+    let vocab_path = std::env::temp_dir().join(format!("submerger-vocab-{}.txt", extension));
+    fs::write(&vocab_path, "GOING\nthe station\nmorgen\nh00ffff\n\n").unwrap();
+    let output_path = std::env::temp_dir().join(format!("submerger-vocab.{}", extension));
+    let output = submerger()
+        .args(inputs.map(fixture))
+        .arg(&output_path)
+        .arg("--vocab")
+        .arg(&vocab_path)
+        .args(args)
+        .run();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&output_path).unwrap();
+    fs::remove_file(output_path).ok();
+    fs::remove_file(vocab_path).ok();
+    content
+}
+
+#[test]
+fn srt_wraps_listed_words_only() {
+    let content = merge(["en.srt", "de.srt"], "srt", &[]);
+    assert!(
+        content.contains("Where are you <font color=\"#ffff00\">going</font>?\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("To <font color=\"#ffff00\">the station</font>.\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("Guten <font color=\"#ffff00\">Morgen</font>.\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("\nGood morning.\n"), "{}", content);
+    assert!(content.contains("\nWohin gehst du?\n"), "{}", content);
+}
+
+#[test]
+fn ass_uses_its_own_template_and_skips_tags() {
+    let content = merge(
+        ["colored.ass", "en.srt"],
+        "ass",
+        &["--vocab-wrap", "ass={\\u1}{}{\\u0}"],
+    );
+    assert!(
+        content.contains(",,Where are you {\\u1}going{\\u0}?\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("{\\c&H00FFFF&}おはよう。"), "{}", content);
+}
+
+#[test]
+fn vtt_defaults_to_bold() {
+    let content = merge(["en.srt", "de.srt"], "vtt", &[]);
+    assert!(
+        content.contains("Where are you <b>going</b>?\n"),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,45 @@
+//! `--merge-window-report`: the second cues of `en.srt` and `de.srt` start
+//! 100ms apart, so a 150ms window groups them and a 50ms one does not.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+
+fn report(window: &str) -> String {
+    let output = submerger()
+        .arg(fixture("en.srt"))
+        .arg(fixture("de.srt"))
+        .args(["--preview", "0", "--merge-strategy", "vertical"])
+        .args(["--bundle-window", window, "--merge-window-report"])
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn describes_a_grouped_pair() {
+    let report = report("150ms");
+    assert!(
+        report.contains(
+            "cue 2  00:00:04,000 --> 00:00:06,300: grouped 2 cues starting within 100ms, window 150ms"
+        ),
+        "{}",
+        report
+    );
+    assert!(report.contains("00:00:04,100  "), "{}", report);
+    assert!(report.contains("de.srt  +100ms"), "{}", report);
+}
+
+#[test]
+fn describes_a_pair_outside_the_window() {
+    let report = report("50ms");
+    assert!(
+        report.contains(
+            "cue 2  00:00:04,000 --> 00:00:06,250: not grouped; the next cue starts 100ms later, window 50ms"
+        ),
+        "{}",
+        report
+    );
+    assert!(report.contains("cue 3  00:00:04,100"), "{}", report);
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "zip")]
+//! `ARCHIVE.zip:ENTRY` inputs: `pack.zip` holds copies of `en.srt` and
+//! `de.srt` under `movie/`.
+
+mod common;
+
+use common::{fixture, submerger, Run};
+use std::process::Output;
+
+fn preview(inputs: [&str; 2]) -> Output {
+    submerger()
+        .args(inputs.map(fixture))
+        .args(["--preview", "9", "--merge-strategy", "vertical"])
+        .run()
+}
+
+#[test]
+fn reads_entries_like_plain_files() {
+    let zipped = preview(["pack.zip:movie/en.srt", "pack.zip:movie/de.srt"]);
+    let stderr = String::from_utf8_lossy(&zipped.stderr);
+    assert!(zipped.status.success(), "{}", stderr);
+    assert!(stderr.contains("Where are you going?"), "{}", stderr);
+    assert_eq!(zipped.stderr, preview(["en.srt", "de.srt"]).stderr);
+}
+
+#[test]
+fn a_missing_entry_names_the_ones_there() {
+    let output = preview(["pack.zip:movie/fr.srt", "de.srt"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr);
+    assert!(
+        stderr.contains("has no entry 'movie/fr.srt'; it holds movie/de.srt, movie/en.srt"),
+        "{}",
+        stderr
+    );
+}